@@ -1,14 +1,15 @@
 use std::io::Result as IoResult;
 
-use oberon::core::linalg::{Point2, Vec2};
+use oberon::core::linalg::Point2;
 use oberon::core::rand::{rng, Rng};
 use oberon::core::style::Color;
 use oberon::core::terminal::Cell;
-use oberon::ecs::World;
+use oberon::ecs::{Query, Res, ResMut, Schedule, Stage, World};
 use oberon::prelude::*;
 
 struct Animation
 {
+    color: Color,
     start_color: Color,
     end_color: Color,
     duration_s: f64,
@@ -20,9 +21,11 @@ impl Animation
     fn new() -> Self
     {
         let mut rng = rng();
+        let start_color = Color::random();
 
         Self {
-            start_color: Color::random(),
+            color: start_color,
+            start_color,
             end_color: Color::BLUE,
             duration_s: rng.random_range(1.0..3.0),
             elapsed: 0.0,
@@ -46,59 +49,51 @@ impl Animation
     }
 }
 
-struct App
+fn spawn_grid(world: &mut World)
 {
-    world: World,
-}
+    let size = world.resource::<Screen>().unwrap().size;
 
-impl App
-{
-    fn new(size: Vec2) -> Self
+    for x in 0..size.x
     {
-        let area = (size.x * size.y) as usize;
-        let world = World::new(area)
-            .register::<Point2>()
-            .register::<Animation>();
-
-        Self { world }
+        for y in 0..size.y
+        {
+            world
+                .spawn()
+                .with(Point2::from_signed(x, y))
+                .with(Animation::new());
+        }
     }
 }
 
-impl ApplicationHandler for App
+fn animate(time: Res<Time>, query: Query<&mut Animation>)
 {
-    fn before_start(&mut self, canvas: Canvas<'_>)
+    for mut animation in &query
     {
-        let size = canvas.size();
-
-        for x in 0..size.x
-        {
-            for y in 0..size.y
-            {
-                self.world
-                    .spawn()
-                    .with(Point2::from_signed(x, y))
-                    .with(Animation::new());
-            }
-        }
+        animation.color = animation.interpolate(time.delta);
     }
+}
 
-    fn frame(&mut self, mut canvas: Canvas<'_>, dt: f64, _: &mut ThreadSafeLoop)
-    {
-        self.world.for_each::<Point2>(|id, pos| {
-            let mut animation = self.world.get_mut::<Animation>(id).unwrap();
-
-            let next_color = animation.interpolate(dt);
-            let cell = Cell::EMPTY.bg(next_color);
+fn draw(mut frame: ResMut<Frame>, query: Query<(&Point2, &Animation)>)
+{
+    let mut canvas = frame.canvas();
 
-            canvas.draw(*pos, cell);
-        });
+    for (pos, animation) in &query
+    {
+        canvas.draw(*pos, Cell::EMPTY.bg(animation.color));
     }
 }
 
 fn main() -> IoResult<()>
 {
     let config = Config::new()?;
-    let app = App::new(config.size);
+
+    let world = World::new().register::<Point2>().register::<Animation>();
+    let schedule = Schedule::new()
+        .add_system(Stage::Startup, spawn_grid)
+        .add_system(Stage::Update, animate)
+        .add_system(Stage::Render, draw);
+
+    let app = EcsApp::new(world, schedule);
 
     Oberon::new(config)?.run(app)
 }
@@ -1,7 +1,7 @@
 use std::io::Result as IoResult;
 
 use oberon::core::linalg::shapes::{ConvexPolygon, Rectangle, Shape, Triangle};
-use oberon::core::linalg::{Point2, Point2f, Vec2};
+use oberon::core::linalg::{Point2f, Vec2f};
 use oberon::core::style::Color;
 use oberon::core::terminal::Cell;
 use oberon::prelude::*;
@@ -12,7 +12,7 @@ struct App
     rectangle: Rectangle,
     triangle: Triangle,
     triangle_rot_point: Point2f,
-    rectangle_trans: Vec2,
+    rectangle_trans: Vec2f,
 }
 
 impl App
@@ -21,27 +21,30 @@ impl App
     {
         Self {
             polygon: ConvexPolygon::from_vertices([
-                Point2::new(30, 30),
-                Point2::new(30, 40),
-                Point2::new(40, 50),
-                Point2::new(50, 40),
-                Point2::new(50, 30),
+                Point2f::new(30.0, 30.0),
+                Point2f::new(30.0, 40.0),
+                Point2f::new(40.0, 50.0),
+                Point2f::new(50.0, 40.0),
+                Point2f::new(50.0, 30.0),
             ]),
-            rectangle: Rectangle::from_corner_and_size(Point2::new(10, 10), Vec2::new(10, 20)),
+            rectangle: Rectangle::from_corner_and_size(
+                Point2f::new(10.0, 10.0),
+                Vec2f::new(10.0, 20.0),
+            ),
             triangle: Triangle::from_vertices([
-                Point2::new(50, 5),
-                Point2::new(40, 10),
-                Point2::new(60, 15),
+                Point2f::new(50.0, 5.0),
+                Point2f::new(40.0, 10.0),
+                Point2f::new(60.0, 15.0),
             ]),
             triangle_rot_point: Point2f::new(70.0, 20.0),
-            rectangle_trans: Vec2::new(1, 0),
+            rectangle_trans: Vec2f::new(1.0, 0.0),
         }
     }
 }
 
 impl ApplicationHandler for App
 {
-    fn frame(&mut self, mut canvas: Canvas<'_>, dt: f64, _: &mut ThreadSafeLoop)
+    fn frame(&mut self, mut canvas: Canvas<'_>, dt: f64, _: f64, _: &mut ThreadSafeLoop)
     {
         canvas.erase();
 
@@ -53,7 +56,7 @@ impl ApplicationHandler for App
         self.rectangle
             .transform()
             .rotate(360.0 * dt / 10.0)
-            .translate(self.rectangle_trans.to_vec2f())
+            .translate(self.rectangle_trans)
             .finalize();
 
         self.triangle
@@ -4,10 +4,18 @@ use oberon_core::canvas::Canvas;
 
 use crate::app_loop::Loop;
 use crate::entrypoint::ThreadSafeLoop;
+use crate::stats::FrameStats;
 
 pub trait ApplicationHandler
 {
     fn before_start(&mut self, _canvas: Canvas<'_>) {}
-    fn frame(&mut self, canvas: Canvas<'_>, dt: f64, app_loop: &mut ThreadSafeLoop);
+    /// Called zero or more times before every frame with the fixed `dt`, if the fixed update is
+    /// enabled in the `Config`.
+    fn fixed_update(&mut self, _dt: f64, _app_loop: &mut ThreadSafeLoop) {}
+    /// The `alpha` tells how far the frame is between the last two fixed updates, it is always
+    /// `1.0` without them.
+    fn frame(&mut self, canvas: Canvas<'_>, dt: f64, alpha: f64, app_loop: &mut ThreadSafeLoop);
+    /// Called once the frame is written to the terminal, with the statistics including it.
+    fn frame_stats(&mut self, _stats: &FrameStats) {}
     fn after_frame(&mut self, _app_loop: &mut Arc<Loop>) {}
 }
@@ -10,6 +10,13 @@ pub struct Config
     pub fps: f32,
     pub cursor_ratio: usize,
     pub size: Vec2,
+    /// Number of `ApplicationHandler::fixed_update` calls per second, `None` disables them. The
+    /// rate has to be positive and finite, `Oberon::new` panics otherwise.
+    pub fixed_update: Option<f64>,
+    /// Most fixed updates run in one frame, the time above it is dropped.
+    pub max_fixed_updates: u32,
+    /// Draws the frame statistics in the top right corner of the screen.
+    pub show_stats: bool,
 }
 
 impl Config
@@ -23,6 +30,9 @@ impl Config
             fps: 60.0,
             cursor_ratio: 2,
             size,
+            fixed_update: None,
+            max_fixed_updates: 5,
+            show_stats: false,
         })
     }
 
@@ -49,4 +59,22 @@ impl Config
         self.size = value;
         self
     }
+
+    pub fn fixed_update(mut self, rate: f64) -> Self
+    {
+        self.fixed_update = Some(rate);
+        self
+    }
+
+    pub fn max_fixed_updates(mut self, value: u32) -> Self
+    {
+        self.max_fixed_updates = value;
+        self
+    }
+
+    pub fn show_stats(mut self, value: bool) -> Self
+    {
+        self.show_stats = value;
+        self
+    }
 }
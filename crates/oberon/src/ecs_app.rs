@@ -0,0 +1,179 @@
+use oberon_core::canvas::Canvas;
+use oberon_core::linalg::Vec2;
+use oberon_core::terminal::Terminal;
+use oberon_ecs::{Schedule, Stage, World};
+
+use crate::application::ApplicationHandler;
+use crate::entrypoint::ThreadSafeLoop;
+use crate::stats::FrameStats;
+use crate::transform::{propagate_transforms, GlobalTransform, Transform};
+
+/// Resource updated before every frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Time
+{
+    /// Seconds since the previous frame.
+    pub delta: f64,
+    /// Seconds since the first frame.
+    pub elapsed: f64,
+    pub frame: u64,
+    /// Seconds between the fixed updates, zero if they are disabled.
+    pub fixed_delta: f64,
+    /// How far the frame is between the last two fixed updates.
+    pub alpha: f64,
+}
+
+/// Resource holding the size of the canvas, inserted before the startup stage.
+#[derive(Clone, Copy, Debug)]
+pub struct Screen
+{
+    pub size: Vec2,
+}
+
+/// Resource the systems of the render stage draw on, copied to the screen after the stage. The
+/// cells drawn in the previous frames are kept until they are redrawn.
+#[derive(Debug)]
+pub struct Frame
+{
+    buffer: Terminal,
+}
+
+/// Application driving the ECS schedule. The startup stage runs before the first frame, the fixed
+/// update stage on every fixed update and the rest of the stages every frame, the render stage
+/// drawing on the `Frame` resource.
+///
+/// The `Time`, `Screen` and `Frame` resources are kept up to date by the application, `FrameStats`
+/// is inserted after the first frame and the transforms are propagated at the end of the post
+/// update stage.
+pub struct EcsApp
+{
+    world: World,
+    schedule: Schedule,
+}
+
+impl Frame
+{
+    fn new(size: Vec2) -> Self
+    {
+        Self {
+            buffer: Terminal::new(size, 1),
+        }
+    }
+
+    pub fn canvas(&mut self) -> Canvas<'_>
+    {
+        self.buffer.canvas()
+    }
+
+    fn copy_to(&mut self, canvas: &mut Canvas<'_>)
+    {
+        canvas.map_cells(|pos, cell| self.buffer.cell(pos).unwrap_or(cell));
+    }
+}
+
+impl EcsApp
+{
+    pub fn new(world: World, schedule: Schedule) -> Self
+    {
+        Self {
+            world: world
+                .debuggable::<Transform>()
+                .debuggable::<GlobalTransform>(),
+            schedule: schedule.add_system(Stage::PostUpdate, propagate_transforms),
+        }
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World
+    {
+        &self.world
+    }
+
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World
+    {
+        &mut self.world
+    }
+}
+
+impl ApplicationHandler for EcsApp
+{
+    fn before_start(&mut self, canvas: Canvas<'_>)
+    {
+        let size = canvas.size();
+
+        self.world.insert_resource(Screen { size });
+        self.world.insert_resource(Frame::new(size));
+        self.world.insert_resource(Time::default());
+        self.schedule.run_startup(&mut self.world);
+    }
+
+    fn fixed_update(&mut self, dt: f64, _: &mut ThreadSafeLoop)
+    {
+        if let Some(mut time) = self.world.resource_mut::<Time>()
+        {
+            time.fixed_delta = dt;
+        }
+        self.schedule.run_stage(Stage::FixedUpdate, &mut self.world);
+    }
+
+    fn frame(&mut self, mut canvas: Canvas<'_>, dt: f64, alpha: f64, _: &mut ThreadSafeLoop)
+    {
+        if let Some(mut time) = self.world.resource_mut::<Time>()
+        {
+            time.delta = dt;
+            time.elapsed += dt;
+            time.frame += 1;
+            time.alpha = alpha;
+        }
+        self.schedule.run(&mut self.world);
+
+        if let Some(mut frame) = self.world.resource_mut::<Frame>()
+        {
+            frame.copy_to(&mut canvas);
+        }
+    }
+
+    fn frame_stats(&mut self, stats: &FrameStats)
+    {
+        self.world.insert_resource(stats.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::sync::Arc;
+
+    use oberon_core::linalg::Point2;
+    use oberon_core::terminal::Cell;
+    use oberon_ecs::{Res, ResMut};
+
+    use super::*;
+    use crate::app_loop::Loop;
+
+    fn draw(mut frame: ResMut<Frame>, time: Res<Time>)
+    {
+        let x = time.frame as usize - 1;
+        frame.canvas().draw(Point2::new(x, 0), Cell::new('#'));
+    }
+
+    #[test]
+    fn render_stage_draws_on_the_screen()
+    {
+        let mut terminal = Terminal::new(Vec2::new(3, 1), 1);
+        let mut app_loop = Arc::new(Loop::default());
+        let mut app = EcsApp::new(
+            World::new(),
+            Schedule::new().add_system(Stage::Render, draw),
+        );
+
+        app.before_start(terminal.canvas());
+        app.frame(terminal.canvas(), 0.1, 1.0, &mut app_loop);
+        app.frame(terminal.canvas(), 0.1, 1.0, &mut app_loop);
+
+        assert_eq!(terminal.cell(Point2::new(0, 0)), Some(Cell::new('#')));
+        assert_eq!(terminal.cell(Point2::new(1, 0)), Some(Cell::new('#')));
+        assert_eq!(terminal.cell(Point2::new(2, 0)), Some(Cell::EMPTY));
+    }
+}
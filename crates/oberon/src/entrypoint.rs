@@ -1,5 +1,6 @@
 use std::io::{stdout, BufWriter, Result as IoResult, Stdout};
 use std::sync::Arc;
+use std::time::Instant;
 
 use oberon_core::renderer::Renderer;
 use oberon_core::terminal::Terminal;
@@ -7,7 +8,9 @@ use oberon_core::terminal::Terminal;
 use crate::app_loop::Loop;
 use crate::application::ApplicationHandler;
 use crate::config::Config;
-use crate::timer::Timer;
+use crate::scene::{Scene, SceneStack};
+use crate::stats::{draw_overlay, FrameSample, FrameStats};
+use crate::timer::{FixedTimestep, Timer};
 use crate::utils::install_cleanup_handlers;
 
 pub type ThreadSafeLoop = Arc<Loop>;
@@ -19,6 +22,10 @@ pub struct Oberon
     renderer: TerminalRenderer,
     terminal: Terminal,
     timer: Timer,
+    fixed: Option<FixedTimestep>,
+    stats: FrameStats,
+    /// Width of the screen in characters, if the statistics are drawn over it.
+    overlay: Option<usize>,
     app_loop: ThreadSafeLoop,
 }
 
@@ -33,6 +40,9 @@ impl Oberon
         let mut renderer = Renderer::new(buf);
         let terminal = Terminal::new(size, config.cursor_ratio);
         let timer = Timer::new(config.fps);
+        let fixed = config
+            .fixed_update
+            .map(|rate| FixedTimestep::new(rate, config.max_fixed_updates));
         let app_loop = Arc::new(Loop::default());
 
         install_cleanup_handlers(app_loop.clone());
@@ -46,10 +56,20 @@ impl Oberon
             renderer,
             terminal,
             timer,
+            fixed,
+            stats: FrameStats::default(),
+            overlay: config.show_stats.then_some(config.size.x as usize),
             app_loop,
         })
     }
 
+    /// Statistics of the latest frames.
+    #[inline]
+    pub fn stats(&self) -> &FrameStats
+    {
+        &self.stats
+    }
+
     pub fn run<A: ApplicationHandler>(&mut self, mut app: A) -> IoResult<()>
     {
         app.before_start(self.terminal.canvas());
@@ -57,15 +77,57 @@ impl Oberon
         while self.app_loop.is_running()
         {
             let dt = self.timer.start_frame();
+            let mut alpha = 1.0;
+
+            if let Some(fixed) = &mut self.fixed
+            {
+                for _ in 0..fixed.advance(dt)
+                {
+                    app.fixed_update(fixed.step(), &mut self.app_loop);
+                }
+                alpha = fixed.alpha();
+            }
+
+            let started = Instant::now();
+            app.frame(self.terminal.canvas(), dt, alpha, &mut self.app_loop);
+            let update = started.elapsed();
+
+            let bytes = self.renderer.bytes_written();
+            let started = Instant::now();
+            let dirty_cells = self.terminal.render_dirty(&mut self.renderer)?;
+            let render = started.elapsed();
+            let bytes = self.renderer.bytes_written() - bytes;
 
-            app.frame(self.terminal.canvas(), dt, &mut self.app_loop);
-            self.terminal.render_frame(&mut self.renderer)?;
+            if let Some(width) = self.overlay
+            {
+                draw_overlay(&mut self.renderer, &self.stats, width)?;
+            }
+
+            let started = Instant::now();
+            self.renderer.flush()?;
+            let flush = started.elapsed();
+
+            self.stats.record(FrameSample {
+                dt,
+                update,
+                render,
+                flush,
+                dirty_cells,
+                bytes,
+            });
+            app.frame_stats(&self.stats);
             app.after_frame(&mut self.app_loop);
 
             self.timer.end_frame();
         }
         Ok(())
     }
+
+    /// Runs the `SceneStack` starting with the scene, until it is empty or the loop is shut down.
+    pub fn run_scene(&mut self, scene: impl Scene + 'static) -> IoResult<()>
+    {
+        self.run(SceneStack::new(scene))
+    }
 }
 
 impl Drop for Oberon
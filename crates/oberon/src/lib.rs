@@ -6,6 +6,18 @@ pub use application::ApplicationHandler;
 mod config;
 pub use config::Config;
 
+mod ecs_app;
+pub use ecs_app::{EcsApp, Frame, Screen, Time};
+
+mod scene;
+pub use scene::{Effect, Scene, SceneStack, Transition};
+
+mod stats;
+pub use stats::{FrameSample, FrameStats};
+
+mod transform;
+pub use transform::{propagate_transforms, GlobalTransform, Transform};
+
 mod entrypoint;
 pub use entrypoint::{Oberon, ThreadSafeLoop};
 pub use {image, oberon_core as core, oberon_ecs as ecs};
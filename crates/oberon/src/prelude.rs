@@ -5,4 +5,8 @@ pub use oberon_core::canvas::Canvas;
 pub use crate::app_loop::Loop;
 pub use crate::application::ApplicationHandler;
 pub use crate::config::Config;
+pub use crate::ecs_app::{EcsApp, Frame, Screen, Time};
 pub use crate::entrypoint::{Oberon, ThreadSafeLoop};
+pub use crate::scene::{Effect, Scene, SceneStack, Transition};
+pub use crate::stats::FrameStats;
+pub use crate::transform::{GlobalTransform, Transform};
@@ -0,0 +1,505 @@
+use oberon_core::canvas::Canvas;
+use oberon_core::linalg::Point2;
+use oberon_core::style::Color;
+use oberon_core::terminal::Cell;
+
+use crate::application::ApplicationHandler;
+use crate::entrypoint::ThreadSafeLoop;
+
+/// Single state of the application, like a menu or a level. The scenes are kept on a
+/// `SceneStack`, only the top one is updated and drawn.
+pub trait Scene
+{
+    /// Called when the scene is put on the stack.
+    fn enter(&mut self) {}
+    /// Called when the scene is taken off the stack.
+    fn exit(&mut self) {}
+    /// Called when another scene is pushed over this one.
+    fn pause(&mut self) {}
+    /// Called when the scene is back on the top of the stack.
+    fn resume(&mut self) {}
+    fn fixed_update(&mut self, _dt: f64) {}
+    /// Draws the frame and tells what the stack should do next. The transition effects are drawn
+    /// over the cells of the frame, so the scene should redraw the whole canvas every frame.
+    fn frame(
+        &mut self, canvas: Canvas<'_>, dt: f64, alpha: f64, app_loop: &mut ThreadSafeLoop,
+    ) -> Transition;
+}
+
+/// Change of the scene stack requested by the top scene.
+pub struct Transition
+{
+    change: Option<Change>,
+    effect: Effect,
+}
+
+enum Change
+{
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+}
+
+/// Drawn while the scenes are switched, the switch happens halfway through the effect.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Effect
+{
+    /// The scenes are switched right after the frame.
+    #[default]
+    Cut,
+    /// Blends the old scene into the color, then the color into the new one. The cells with the
+    /// default color change at once, halfway through each of the halves.
+    Fade
+    {
+        duration: f64, color: Color
+    },
+    /// Covers the old scene with the color from the left, then uncovers the new one.
+    Wipe
+    {
+        duration: f64, color: Color
+    },
+}
+
+/// Application running the scene on the top of the stack, it stops once the stack is empty.
+pub struct SceneStack
+{
+    scenes: Vec<Box<dyn Scene>>,
+    pending: Option<Change>,
+    effect: Effect,
+    elapsed: f64,
+}
+
+impl Transition
+{
+    /// Keeps the current scene.
+    pub const NONE: Self = Self {
+        change: None,
+        effect: Effect::Cut,
+    };
+
+    /// Pauses the current scene and enters the new one.
+    pub fn push(scene: impl Scene + 'static) -> Self
+    {
+        Self::new(Change::Push(Box::new(scene)))
+    }
+
+    /// Exits the current scene and resumes the one below it.
+    pub fn pop() -> Self
+    {
+        Self::new(Change::Pop)
+    }
+
+    /// Exits the current scene and enters the new one in its place.
+    pub fn replace(scene: impl Scene + 'static) -> Self
+    {
+        Self::new(Change::Replace(Box::new(scene)))
+    }
+
+    pub fn effect(mut self, effect: Effect) -> Self
+    {
+        self.effect = effect;
+        self
+    }
+
+    fn new(change: Change) -> Self
+    {
+        Self {
+            change: Some(change),
+            effect: Effect::Cut,
+        }
+    }
+}
+
+impl Effect
+{
+    fn duration(&self) -> f64
+    {
+        match *self
+        {
+            Self::Cut => 0.0,
+            Self::Fade { duration, .. } | Self::Wipe { duration, .. } => duration,
+        }
+    }
+
+    // The `progress` goes from 0 to 1, the scenes are switched at 0.5.
+    fn draw(&self, canvas: &mut Canvas<'_>, progress: f64)
+    {
+        // How much of the scene is covered, the most in the middle of the effect.
+        let cover = 1.0 - (2.0 * progress - 1.0).abs();
+
+        match *self
+        {
+            Self::Cut => (),
+            Self::Fade { color, .. } => canvas.map_cells(|_, cell| Cell {
+                bg: blend(cell.bg, color, cover),
+                fg: blend(cell.fg, color, cover),
+                ..cell
+            }),
+            Self::Wipe { color, .. } =>
+            {
+                let width = canvas.size().x as f64;
+                let covered = if progress < 0.5
+                {
+                    0.0..cover * width
+                }
+                else
+                {
+                    (1.0 - cover) * width..width
+                };
+
+                canvas.map_cells(|Point2 { x, .. }, cell| {
+                    if covered.contains(&(x as f64))
+                    {
+                        Cell::EMPTY.bg(color)
+                    }
+                    else
+                    {
+                        cell
+                    }
+                });
+            }
+        }
+    }
+}
+
+fn blend(color: Color, target: Color, ratio: f64) -> Color
+{
+    match color
+    {
+        Color::Default if ratio >= 0.5 => target,
+        color => color.mix(target, ratio),
+    }
+}
+
+impl SceneStack
+{
+    pub fn new(scene: impl Scene + 'static) -> Self
+    {
+        Self {
+            scenes: vec![Box::new(scene)],
+            pending: None,
+            effect: Effect::Cut,
+            elapsed: 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.scenes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.scenes.is_empty()
+    }
+
+    /// Whether a transition effect is being drawn, the transitions requested in the meantime are
+    /// ignored.
+    #[inline]
+    pub fn in_transition(&self) -> bool
+    {
+        self.effect != Effect::Cut
+    }
+
+    fn apply(&mut self, change: Change)
+    {
+        match change
+        {
+            Change::Push(mut scene) =>
+            {
+                if let Some(top) = self.scenes.last_mut()
+                {
+                    top.pause();
+                }
+                scene.enter();
+                self.scenes.push(scene);
+            }
+            Change::Pop =>
+            {
+                if let Some(mut top) = self.scenes.pop()
+                {
+                    top.exit();
+                }
+                if let Some(top) = self.scenes.last_mut()
+                {
+                    top.resume();
+                }
+            }
+            Change::Replace(mut scene) =>
+            {
+                if let Some(mut top) = self.scenes.pop()
+                {
+                    top.exit();
+                }
+                scene.enter();
+                self.scenes.push(scene);
+            }
+        }
+    }
+
+    fn start(&mut self, transition: Transition)
+    {
+        let Some(change) = transition.change
+        else
+        {
+            return;
+        };
+
+        if transition.effect.duration() > 0.0
+        {
+            self.pending = Some(change);
+            self.effect = transition.effect;
+            self.elapsed = 0.0;
+        }
+        else
+        {
+            self.apply(change);
+        }
+    }
+}
+
+impl ApplicationHandler for SceneStack
+{
+    fn before_start(&mut self, _: Canvas<'_>)
+    {
+        self.scenes.iter_mut().for_each(|scene| scene.enter());
+    }
+
+    fn fixed_update(&mut self, dt: f64, _: &mut ThreadSafeLoop)
+    {
+        if let Some(top) = self.scenes.last_mut()
+        {
+            top.fixed_update(dt);
+        }
+    }
+
+    fn frame(&mut self, mut canvas: Canvas<'_>, dt: f64, alpha: f64, app_loop: &mut ThreadSafeLoop)
+    {
+        let effect = self.effect;
+        let progress = if self.in_transition()
+        {
+            (self.elapsed / effect.duration()).min(1.0)
+        }
+        else
+        {
+            0.0
+        };
+
+        if progress >= 0.5
+        {
+            if let Some(change) = self.pending.take()
+            {
+                self.apply(change);
+            }
+        }
+
+        let Some(top) = self.scenes.last_mut()
+        else
+        {
+            app_loop.shutdown();
+            return;
+        };
+
+        let transition = top.frame(canvas.reborrow(), dt, alpha, app_loop);
+
+        if self.in_transition()
+        {
+            effect.draw(&mut canvas, progress);
+            self.elapsed += dt;
+
+            if progress >= 1.0
+            {
+                self.effect = Effect::Cut;
+            }
+        }
+        else
+        {
+            self.start(transition);
+        }
+
+        if self.scenes.is_empty()
+        {
+            app_loop.shutdown();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use oberon_core::linalg::Vec2;
+    use oberon_core::terminal::Terminal;
+
+    use super::*;
+    use crate::app_loop::Loop;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    struct Logged
+    {
+        name: &'static str,
+        log: Log,
+        next: Option<Transition>,
+    }
+
+    impl Logged
+    {
+        fn new(name: &'static str, log: &Log) -> Self
+        {
+            Self {
+                name,
+                log: log.clone(),
+                next: None,
+            }
+        }
+
+        fn then(mut self, transition: Transition) -> Self
+        {
+            self.next = Some(transition);
+            self
+        }
+
+        fn record(&self, event: &str)
+        {
+            self.log
+                .borrow_mut()
+                .push(format!("{} {}", self.name, event));
+        }
+    }
+
+    impl Scene for Logged
+    {
+        fn enter(&mut self)
+        {
+            self.record("enter");
+        }
+
+        fn exit(&mut self)
+        {
+            self.record("exit");
+        }
+
+        fn pause(&mut self)
+        {
+            self.record("pause");
+        }
+
+        fn resume(&mut self)
+        {
+            self.record("resume");
+        }
+
+        fn frame(
+            &mut self, mut canvas: Canvas<'_>, _: f64, _: f64, _: &mut ThreadSafeLoop,
+        ) -> Transition
+        {
+            self.record("frame");
+            canvas.fill(Cell::new(self.name.chars().next().unwrap()).bg(Color::WHITE));
+            self.next.take().unwrap_or(Transition::NONE)
+        }
+    }
+
+    fn run_frames(
+        stack: &mut SceneStack, terminal: &mut Terminal, frames: usize, dt: f64,
+    ) -> ThreadSafeLoop
+    {
+        let mut app_loop = Arc::new(Loop::default());
+
+        for _ in 0..frames
+        {
+            stack.frame(terminal.canvas(), dt, 1.0, &mut app_loop);
+        }
+        app_loop
+    }
+
+    #[test]
+    fn push_pop_and_replace_call_the_hooks()
+    {
+        let log = Log::default();
+        let mut terminal = Terminal::new(Vec2::new(4, 2), 1);
+
+        let popping = Logged::new("pause menu", &log).then(Transition::pop());
+        let level = Logged::new("level", &log).then(Transition::push(popping));
+        let menu = Logged::new("menu", &log).then(Transition::replace(level));
+        let mut stack = SceneStack::new(menu);
+
+        stack.before_start(terminal.canvas());
+        let app_loop = run_frames(&mut stack, &mut terminal, 4, 0.1);
+
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "menu enter",
+                "menu frame",
+                "menu exit",
+                "level enter",
+                "level frame",
+                "level pause",
+                "pause menu enter",
+                "pause menu frame",
+                "pause menu exit",
+                "level resume",
+                "level frame",
+            ]
+        );
+        assert_eq!(stack.len(), 1);
+        assert!(app_loop.is_running());
+    }
+
+    #[test]
+    fn stop_when_the_last_scene_is_popped()
+    {
+        let log = Log::default();
+        let mut terminal = Terminal::new(Vec2::new(4, 2), 1);
+        let mut stack = SceneStack::new(Logged::new("only", &log).then(Transition::pop()));
+
+        let app_loop = run_frames(&mut stack, &mut terminal, 1, 0.1);
+
+        assert!(stack.is_empty());
+        assert!(!app_loop.is_running());
+    }
+
+    #[test]
+    fn switch_scenes_halfway_through_the_effect()
+    {
+        let log = Log::default();
+        let mut terminal = Terminal::new(Vec2::new(4, 2), 1);
+
+        let effect = Effect::Wipe {
+            duration: 1.0,
+            color: Color::BLACK,
+        };
+        let next = Logged::new("next", &log);
+        let first = Logged::new("first", &log).then(Transition::replace(next).effect(effect));
+        let mut stack = SceneStack::new(first);
+
+        run_frames(&mut stack, &mut terminal, 3, 0.25);
+
+        assert!(stack.in_transition());
+        assert_eq!(terminal.cell(Point2::new(2, 0)).unwrap().char, 'f');
+        assert_eq!(
+            terminal.cell(Point2::new(0, 0)),
+            Some(Cell::EMPTY.bg(Color::BLACK))
+        );
+
+        run_frames(&mut stack, &mut terminal, 1, 0.25);
+
+        assert_eq!(stack.len(), 1);
+        assert_eq!(
+            terminal.cell(Point2::new(0, 1)),
+            Some(Cell::EMPTY.bg(Color::BLACK))
+        );
+
+        run_frames(&mut stack, &mut terminal, 2, 0.25);
+
+        assert!(!stack.in_transition());
+        assert_eq!(terminal.cell(Point2::new(0, 0)).unwrap().char, 'n');
+        assert!(log.borrow().contains(&"first exit".to_string()));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+use std::io::{Result as IoResult, Write};
+use std::time::Duration;
+
+use oberon_core::linalg::Point2;
+use oberon_core::renderer::Renderer;
+use oberon_core::style::Rgb;
+
+/// Number of the latest frames the statistics are computed from.
+const SAMPLES: usize = 120;
+const OVERLAY_WIDTH: usize = 18;
+
+/// Measurements of a single frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameSample
+{
+    /// Seconds since the previous frame.
+    pub dt: f64,
+    /// Time spent in `ApplicationHandler::frame`.
+    pub update: Duration,
+    /// Time spent writing the dirty cells.
+    pub render: Duration,
+    /// Time spent flushing the written cells to the terminal.
+    pub flush: Duration,
+    pub dirty_cells: usize,
+    pub bytes: usize,
+}
+
+/// Rolling statistics of the latest frames, passed to `ApplicationHandler::frame_stats` after
+/// every frame.
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats
+{
+    samples: VecDeque<FrameSample>,
+}
+
+impl FrameSample
+{
+    /// Time the frame took without the sleep between the frames.
+    #[inline]
+    pub fn work(&self) -> Duration
+    {
+        self.update + self.render + self.flush
+    }
+}
+
+impl FrameStats
+{
+    #[inline]
+    pub fn last(&self) -> Option<&FrameSample>
+    {
+        self.samples.back()
+    }
+
+    /// The latest samples, from the oldest one.
+    pub fn samples(&self) -> impl Iterator<Item = &FrameSample>
+    {
+        self.samples.iter()
+    }
+
+    pub fn fps(&self) -> f64
+    {
+        let elapsed: f64 = self.samples.iter().map(|sample| sample.dt).sum();
+
+        if elapsed > 0.0
+        {
+            self.samples.len() as f64 / elapsed
+        }
+        else
+        {
+            0.0
+        }
+    }
+
+    /// Work time which the given fraction of the latest frames did not exceed, the `percentile`
+    /// is clamped to `0.0..=1.0`.
+    pub fn percentile(&self, percentile: f64) -> Duration
+    {
+        let mut work: Vec<_> = self.samples.iter().map(FrameSample::work).collect();
+
+        if work.is_empty()
+        {
+            return Duration::ZERO;
+        }
+        work.sort_unstable();
+
+        let rank = (percentile.clamp(0.0, 1.0) * work.len() as f64).ceil() as usize;
+        work[rank.saturating_sub(1)]
+    }
+
+    pub(crate) fn record(&mut self, sample: FrameSample)
+    {
+        if self.samples.len() == SAMPLES
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+/// Writes the statistics straight to the renderer, over the top right corner of the screen of
+/// the given width in characters. The cells below are not touched, so the overlay is redrawn
+/// every frame.
+pub(crate) fn draw_overlay<W: Write>(
+    renderer: &mut Renderer<W>, stats: &FrameStats, width: usize,
+) -> IoResult<()>
+{
+    let last = stats.last().copied().unwrap_or_default();
+    let lines = [
+        format!("fps {:.1}", stats.fps()),
+        format!("p50 {:.2}ms", millis(stats.percentile(0.5))),
+        format!("p99 {:.2}ms", millis(stats.percentile(0.99))),
+        format!("cells {}", last.dirty_cells),
+        format!("bytes {}", last.bytes),
+    ];
+    let column = width.saturating_sub(OVERLAY_WIDTH);
+
+    renderer.change_bg(&Rgb::BLACK)?;
+    renderer.change_fg(&Rgb::WHITE)?;
+
+    for (row, line) in lines.iter().enumerate()
+    {
+        renderer.move_cursor(Point2::new(column, row))?;
+
+        for char in format!(" {line:<width$}", width = OVERLAY_WIDTH - 1)
+            .chars()
+            .take(width)
+        {
+            renderer.write(char)?;
+        }
+    }
+    renderer.move_cursor(Point2::ZERO)
+}
+
+#[inline]
+fn millis(duration: Duration) -> f64
+{
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn sample(dt: f64, work: u64) -> FrameSample
+    {
+        FrameSample {
+            dt,
+            update: Duration::from_millis(work),
+            ..FrameSample::default()
+        }
+    }
+
+    #[test]
+    fn fps_and_percentiles()
+    {
+        let mut stats = FrameStats::default();
+
+        assert_eq!(stats.fps(), 0.0);
+        assert_eq!(stats.percentile(0.5), Duration::ZERO);
+
+        for work in 1..=10
+        {
+            stats.record(sample(0.05, work));
+        }
+
+        assert!((stats.fps() - 20.0).abs() < 1e-9);
+        assert_eq!(stats.percentile(0.5), Duration::from_millis(5));
+        assert_eq!(stats.percentile(0.99), Duration::from_millis(10));
+        assert_eq!(stats.percentile(0.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn keep_only_latest_samples()
+    {
+        let mut stats = FrameStats::default();
+
+        for work in 0..SAMPLES as u64 + 5
+        {
+            stats.record(sample(0.01, work));
+        }
+
+        assert_eq!(stats.samples().count(), SAMPLES);
+        assert_eq!(
+            stats.samples().next().unwrap().update,
+            Duration::from_millis(5)
+        );
+        assert_eq!(
+            stats.last().unwrap().update,
+            Duration::from_millis(SAMPLES as u64 + 4)
+        );
+    }
+}
@@ -1,6 +1,15 @@
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
+/// Accumulates the frame time and splits it into fixed steps.
+#[derive(Debug)]
+pub(crate) struct FixedTimestep
+{
+    step: f64,
+    accumulator: f64,
+    max_steps: u32,
+}
+
 #[derive(Debug)]
 pub(crate) struct Timer
 {
@@ -32,3 +41,81 @@ impl Timer
         sleep(sleep_time);
     }
 }
+
+impl FixedTimestep
+{
+    pub fn new(rate: f64, max_steps: u32) -> Self
+    {
+        assert!(
+            rate > 0.0 && rate.is_finite(),
+            "Fixed update rate has to be positive and finite, got `{rate}`."
+        );
+
+        Self {
+            step: 1.0 / rate,
+            accumulator: 0.0,
+            max_steps: max_steps.max(1),
+        }
+    }
+
+    #[inline]
+    pub fn step(&self) -> f64
+    {
+        self.step
+    }
+
+    /// Returns how many steps fit into the time accumulated so far. The frame time is clamped to
+    /// `max_steps` steps, so a slow frame does not make the next ones even slower.
+    pub fn advance(&mut self, dt: f64) -> u32
+    {
+        self.accumulator += dt.min(self.step * f64::from(self.max_steps));
+
+        let steps = (self.accumulator / self.step) as u32;
+        self.accumulator -= f64::from(steps) * self.step;
+        steps
+    }
+
+    /// Fraction of the next step which has already passed, used to interpolate between the last
+    /// two fixed states.
+    #[inline]
+    pub fn alpha(&self) -> f64
+    {
+        self.accumulator / self.step
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn accumulate_partial_steps()
+    {
+        let mut timestep = FixedTimestep::new(4.0, 5);
+
+        assert_eq!(timestep.advance(0.125), 0);
+        assert_eq!(timestep.alpha(), 0.5);
+        assert_eq!(timestep.advance(0.5), 2);
+        assert_eq!(timestep.alpha(), 0.5);
+        assert_eq!(timestep.advance(0.125), 1);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn clamp_long_frames()
+    {
+        let mut timestep = FixedTimestep::new(10.0, 3);
+
+        assert_eq!(timestep.advance(5.0), 3);
+        assert!(timestep.alpha() < 1.0);
+        assert_eq!(timestep.advance(0.0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fixed update rate has to be positive")]
+    fn reject_zero_rate()
+    {
+        FixedTimestep::new(0.0, 5);
+    }
+}
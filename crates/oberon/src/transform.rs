@@ -0,0 +1,146 @@
+use oberon_core::linalg::{Matrix3, Transform2D};
+use oberon_ecs::{Children, Entity, Parent, Query, With, Without};
+
+/// Transformation of the entity relative to its parent, or to the world for the entities without
+/// a parent.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Transform(pub Transform2D);
+
+/// Transformation of the entity relative to the world, computed from the `Transform` of the
+/// entity and of all its ancestors by `propagate_transforms`. It is a matrix, since a non-uniform
+/// scale of a rotated child shears it.
+#[derive(Clone, Copy, Debug)]
+pub struct GlobalTransform(pub Matrix3);
+
+impl Default for GlobalTransform
+{
+    fn default() -> Self
+    {
+        Self(Matrix3::IDENTITY)
+    }
+}
+
+/// Updates the `GlobalTransform` of every entity having both of the transforms. The hierarchy is
+/// walked from the roots down, an entity without the transforms stops the propagation to its
+/// descendants. Only the transforms which differ are written, so `Changed<GlobalTransform>` sees
+/// just the entities which moved.
+pub fn propagate_transforms(
+    roots: Query<Entity, (With<Transform>, Without<Parent>)>,
+    nodes: Query<(&Transform, &mut GlobalTransform, Option<&Children>)>,
+)
+{
+    for root in &roots
+    {
+        propagate(&nodes, root, Matrix3::IDENTITY);
+    }
+}
+
+fn propagate(
+    nodes: &Query<(&Transform, &mut GlobalTransform, Option<&Children>)>, entity: Entity,
+    parent: Matrix3,
+)
+{
+    let Some((local, mut global, children)) = nodes.get(entity)
+    else
+    {
+        return;
+    };
+
+    let matrix = parent * local.0.to_matrix();
+
+    if global.0.data != matrix.data
+    {
+        global.0 = matrix;
+    }
+
+    let children = children.map(|children| children.to_vec());
+    drop(global);
+
+    for child in children.into_iter().flatten()
+    {
+        propagate(nodes, child, matrix);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use oberon_core::linalg::{Point2f, Vec2f};
+    use oberon_ecs::{Changed, Schedule, Stage, World};
+
+    use super::*;
+
+    #[test]
+    fn children_move_with_parent()
+    {
+        let mut world = World::new()
+            .register::<Transform>()
+            .register::<GlobalTransform>();
+
+        let ship = world
+            .spawn()
+            .with(Transform(
+                Transform2D::IDENTITY.translate(Vec2f::new(10.0, 0.0)),
+            ))
+            .with(GlobalTransform::default())
+            .into_id();
+        let turret = world
+            .spawn()
+            .with(Transform(
+                Transform2D::IDENTITY.translate(Vec2f::new(0.0, 2.0)),
+            ))
+            .with(GlobalTransform::default())
+            .with_parent(ship)
+            .into_id();
+
+        let mut schedule = Schedule::new().add_system(Stage::PostUpdate, propagate_transforms);
+        schedule.run(&mut world);
+
+        let position =
+            |world: &World| world.get::<GlobalTransform>(turret).unwrap().0 * Point2f::ZERO;
+
+        assert_eq!(position(&world), Point2f::new(10.0, 2.0));
+
+        world.get_mut::<Transform>(ship).unwrap().0.translation = Vec2f::new(-5.0, 1.0);
+        schedule.run(&mut world);
+
+        assert_eq!(position(&world), Point2f::new(-5.0, 3.0));
+    }
+
+    #[test]
+    fn unchanged_transforms_are_not_written()
+    {
+        let mut world = World::new()
+            .register::<Transform>()
+            .register::<GlobalTransform>();
+
+        let ship = world
+            .spawn()
+            .with(Transform::default())
+            .with(GlobalTransform::default())
+            .into_id();
+        world
+            .spawn()
+            .with(Transform(Transform2D::IDENTITY.rotate(90.0)))
+            .with(GlobalTransform::default())
+            .with_parent(ship);
+
+        let mut schedule = Schedule::new().add_system(Stage::PostUpdate, propagate_transforms);
+        let changed = |world: &World| {
+            world
+                .query_filtered::<Entity, Changed<GlobalTransform>>()
+                .iter()
+                .count()
+        };
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(changed(&world), 0);
+
+        world.get_mut::<Transform>(ship).unwrap().0.rotation = 45.0;
+        schedule.run(&mut world);
+
+        assert_eq!(changed(&world), 2);
+    }
+}
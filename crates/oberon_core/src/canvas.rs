@@ -1,5 +1,5 @@
-use crate::linalg::shapes::Shape;
-use crate::linalg::{Point2, Vec2};
+use crate::linalg::shapes::{Aabb, Shape};
+use crate::linalg::{Point2, Point2f, Vec2};
 use crate::terminal::{Cell, Terminal};
 
 pub struct Canvas<'a>
@@ -14,6 +14,12 @@ impl<'a> Canvas<'a>
         Self { terminal }
     }
 
+    /// Canvas drawing on the same terminal, which can be passed on by value.
+    pub fn reborrow(&mut self) -> Canvas<'_>
+    {
+        Canvas::new(self.terminal)
+    }
+
     pub fn area(&self) -> f64
     {
         self.terminal.area()
@@ -24,22 +30,51 @@ impl<'a> Canvas<'a>
         self.terminal.size()
     }
 
+    /// Positions outside of the canvas are ignored.
     pub fn draw(&mut self, pos: Point2, cell: Cell)
     {
-        self.terminal.at(pos).change_cell(cell);
+        let size = self.size();
+
+        if pos.x < size.x as usize && pos.y < size.y as usize
+        {
+            self.terminal.at(pos).change_cell(cell);
+        }
+    }
+
+    /// Cell drawn at the position, also the one drawn in the previous frames if it was not
+    /// redrawn since. `None` outside of the canvas.
+    pub fn cell(&self, pos: Point2) -> Option<Cell>
+    {
+        self.terminal.cell(pos)
+    }
+
+    /// Redraws every cell with the result of `f` called with its position and the current cell.
+    pub fn map_cells(&mut self, f: impl FnMut(Point2, Cell) -> Cell)
+    {
+        self.terminal.map_cells(f);
     }
 
     pub fn draw_shape<S: Shape>(&mut self, shape: &S, cell: Cell)
     {
+        let size = self.size();
+        let visible = Aabb::new(
+            Point2f::ZERO,
+            Point2f::new((size.x - 1) as f64, (size.y - 1) as f64),
+        );
+
         shape
-            .points_filled()
+            .points_filled_within(&visible)
+            .filter_map(|point| clip(point, size))
             .for_each(|point| self.draw(point, cell));
     }
 
     pub fn draw_shape_outline<S: Shape>(&mut self, shape: &S, cell: Cell)
     {
+        let size = self.size();
+
         shape
             .points_outline()
+            .filter_map(|point| clip(point, size))
             .for_each(|point| self.draw(point, cell));
     }
 
@@ -53,3 +88,130 @@ impl<'a> Canvas<'a>
         self.terminal.fill(cell);
     }
 }
+
+// Converts signed cell coordinates into a position on the canvas, if they fit.
+#[inline]
+fn clip(point: Vec2, size: Vec2) -> Option<Point2>
+{
+    let fits_x = (0..size.x).contains(&point.x);
+    let fits_y = (0..size.y).contains(&point.y);
+
+    (fits_x && fits_y).then(|| Point2::from_signed(point.x, point.y))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::linalg::shapes::{Rectangle, Triangle};
+    use crate::linalg::{Point2f, Vec2f};
+    use crate::renderer::Renderer;
+
+    #[test]
+    fn clip_points_outside_of_the_canvas()
+    {
+        let size = Vec2::new(10, 5);
+
+        assert!(clip(Vec2::new(-1, 0), size).is_none());
+        assert!(clip(Vec2::new(0, 5), size).is_none());
+        assert!(clip(Vec2::new(10, 4), size).is_none());
+
+        let clipped = clip(Vec2::new(9, 4), size).unwrap();
+
+        assert_eq!((clipped.x, clipped.y), (9, 4));
+    }
+
+    #[test]
+    fn draw_shapes_partially_outside_of_the_canvas()
+    {
+        let mut terminal = Terminal::new(Vec2::new(10, 5), 1);
+        let mut canvas = terminal.canvas();
+
+        let rectangle =
+            Rectangle::from_corner_and_size(Point2f::new(-5.0, -5.0), Vec2f::new(8.0, 20.0));
+        let triangle = Triangle::from_vertices([
+            Point2f::new(8.0, 2.0),
+            Point2f::new(14.0, -3.0),
+            Point2f::new(14.0, 9.0),
+        ]);
+
+        canvas.draw_shape(&rectangle, Cell::new('#'));
+        canvas.draw_shape_outline(&triangle, Cell::new('@'));
+        canvas.draw(Point2::new(10, 0), Cell::new('!'));
+
+        let rows: Vec<String> = (0..5)
+            .map(|y| {
+                (0..10)
+                    .map(|x| canvas.cell(Point2::new(x, y)).unwrap().char)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "####      ",
+                "####     @",
+                "####    @ ",
+                "####     @",
+                "####      ",
+            ]
+        );
+    }
+
+    #[test]
+    fn map_cells_by_position()
+    {
+        let mut terminal = Terminal::new(Vec2::new(4, 3), 2);
+        let mut canvas = terminal.canvas();
+
+        canvas.fill(Cell::new('.'));
+        canvas.map_cells(|pos, cell| {
+            if pos.x == 3
+            {
+                Cell::new('|')
+            }
+            else
+            {
+                cell
+            }
+        });
+
+        assert_eq!(canvas.cell(Point2::new(2, 1)), Some(Cell::new('.')));
+        assert_eq!(canvas.cell(Point2::new(3, 2)), Some(Cell::new('|')));
+        assert_eq!(canvas.cell(Point2::new(4, 0)), None);
+        assert_eq!(canvas.cell(Point2::new(0, 3)), None);
+    }
+
+    #[test]
+    fn no_cell_at_huge_coordinates()
+    {
+        let mut terminal = Terminal::new(Vec2::new(4, 3), 1);
+        let canvas = terminal.canvas();
+
+        assert_eq!(canvas.cell(Point2::new(0, usize::MAX)), None);
+        assert_eq!(canvas.cell(Point2::new(usize::MAX, usize::MAX)), None);
+    }
+
+    #[test]
+    fn map_cells_redraws_only_changed_cells()
+    {
+        let mut terminal = Terminal::new(Vec2::new(4, 3), 1);
+        let mut renderer = Renderer::new(Vec::new());
+
+        terminal.canvas().fill(Cell::new('.'));
+        terminal.render_dirty(&mut renderer).unwrap();
+
+        terminal.canvas().map_cells(|pos, cell| {
+            if pos == Point2::new(1, 2)
+            {
+                Cell::new('#')
+            }
+            else
+            {
+                cell
+            }
+        });
+
+        assert_eq!(terminal.render_dirty(&mut renderer).unwrap(), 1);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod canvas;
 pub mod linalg;
 pub mod renderer;
+pub mod spatial;
 pub mod style;
 pub mod sys;
 pub mod terminal;
@@ -1,4 +1,4 @@
-use crate::linalg::Point2;
+use crate::linalg::Vec2;
 
 #[derive(Debug)]
 pub struct Bresenham
@@ -16,10 +16,10 @@ pub struct Bresenham
 
 impl Bresenham
 {
-    pub const fn new(p0: Point2, p1: Point2) -> Self
+    pub const fn new(p0: Vec2, p1: Vec2) -> Self
     {
-        let dx = (p1.x as isize - p0.x as isize).abs();
-        let dy = (p1.y as isize - p0.y as isize).abs();
+        let dx = (p1.x - p0.x).abs();
+        let dy = (p1.y - p0.y).abs();
 
         let err = dx - dy;
 
@@ -27,10 +27,10 @@ impl Bresenham
         let sy = if p0.y < p1.y { 1 } else { -1 };
 
         Self {
-            x: p0.x as isize,
-            y: p0.y as isize,
-            end_x: p1.x as isize,
-            end_y: p1.y as isize,
+            x: p0.x,
+            y: p0.y,
+            end_x: p1.x,
+            end_y: p1.y,
             sx,
             sy,
             dx,
@@ -42,7 +42,7 @@ impl Bresenham
 
 impl Iterator for Bresenham
 {
-    type Item = Point2;
+    type Item = Vec2;
 
     // Source: https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
     fn next(&mut self) -> Option<Self::Item>
@@ -65,6 +65,6 @@ impl Iterator for Bresenham
             self.y += self.sy;
         }
 
-        Some(Point2::from_signed(self.x, self.y))
+        Some(Vec2::new(self.x, self.y))
     }
 }
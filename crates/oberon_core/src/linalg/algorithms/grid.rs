@@ -0,0 +1,45 @@
+use crate::linalg::Vec2;
+
+/// Iterates over every cell of the grid between the corners, both of them inclusive.
+#[derive(Debug)]
+pub struct GridCells
+{
+    x: isize,
+    y: isize,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl GridCells
+{
+    pub const fn new(min: Vec2, max: Vec2) -> Self
+    {
+        let x = min.x;
+        let y = min.y;
+
+        Self { x, y, min, max }
+    }
+}
+
+impl Iterator for GridCells
+{
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.y > self.max.y || self.min.x > self.max.x
+        {
+            return None;
+        }
+
+        let point = Vec2::new(self.x, self.y);
+        self.x += 1;
+
+        if self.x > self.max.x
+        {
+            self.x = self.min.x;
+            self.y += 1;
+        }
+        Some(point)
+    }
+}
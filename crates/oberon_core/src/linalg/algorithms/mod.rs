@@ -1,2 +1,5 @@
 mod bresenham;
 pub use bresenham::Bresenham;
+
+mod grid;
+pub use grid::GridCells;
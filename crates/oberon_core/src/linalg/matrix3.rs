@@ -1,6 +1,6 @@
 use std::ops::{Mul, MulAssign};
 
-use crate::linalg::{Point2f, Vec2f};
+use crate::linalg::{Point2f, Transform2D, Vec2f};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Matrix3
@@ -17,6 +17,11 @@ impl Matrix3
         Self { data }
     }
 
+    pub fn rotation(angle: f64) -> Self
+    {
+        Self::rotation_around(Point2f::ZERO, angle)
+    }
+
     pub fn rotation_around(point: Point2f, angle: f64) -> Self
     {
         let (sin, cos) = angle.to_radians().sin_cos();
@@ -42,6 +47,159 @@ impl Matrix3
 
         matrix
     }
+
+    pub fn scaling(by: Vec2f) -> Self
+    {
+        let mut matrix = Self::IDENTITY;
+
+        matrix.data[0] = by.x;
+        matrix.data[4] = by.y;
+
+        matrix
+    }
+
+    pub fn scaling_around(point: Point2f, by: Vec2f) -> Self
+    {
+        Self::scaling(by).around(point)
+    }
+
+    pub fn shearing(by: Vec2f) -> Self
+    {
+        let mut matrix = Self::IDENTITY;
+
+        matrix.data[1] = by.x;
+        matrix.data[3] = by.y;
+
+        matrix
+    }
+
+    pub fn shearing_around(point: Point2f, by: Vec2f) -> Self
+    {
+        Self::shearing(by).around(point)
+    }
+
+    /// Reflection across the line going through the origin along the `axis`.
+    pub fn reflection(axis: Vec2f) -> Self
+    {
+        let xx = axis.x * axis.x;
+        let yy = axis.y * axis.y;
+        let xy = axis.x * axis.y;
+        let length = xx + yy;
+
+        let mut matrix = Self::IDENTITY;
+
+        matrix.data[0] = (xx - yy) / length;
+        matrix.data[1] = 2.0 * xy / length;
+        matrix.data[3] = 2.0 * xy / length;
+        matrix.data[4] = (yy - xx) / length;
+
+        matrix
+    }
+
+    /// Reflection across the line going through the `point` along the `axis`.
+    pub fn reflection_around(point: Point2f, axis: Vec2f) -> Self
+    {
+        Self::reflection(axis).around(point)
+    }
+
+    pub fn determinant(&self) -> f64
+    {
+        let m = &self.data;
+
+        m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6])
+            + m[2] * (m[3] * m[7] - m[4] * m[6])
+    }
+
+    /// Splits the transformation into translation, rotation and scale. Shearing cannot be
+    /// represented this way and gets lost, reflections end up as a negative vertical scale.
+    pub fn decompose(&self) -> Transform2D
+    {
+        let m = &self.data;
+
+        let translation = Vec2f::new(m[2], m[5]);
+        let scale_x = m[0].hypot(m[3]);
+        let rotation = m[3].atan2(m[0]).to_degrees();
+        let scale_y = if scale_x != 0.0
+        {
+            (m[0] * m[4] - m[1] * m[3]) / scale_x
+        }
+        else
+        {
+            m[1].hypot(m[4])
+        };
+
+        Transform2D::new(translation, rotation, Vec2f::new(scale_x, scale_y))
+    }
+
+    /// Inverse transformation, `None` when the matrix is singular. The determinant is compared
+    /// to the product of the column lengths, its upper bound, so the answer does not depend on the
+    /// scale of the matrix.
+    pub fn inverse(&self) -> Option<Self>
+    {
+        let determinant = self.determinant();
+        let m = &self.data;
+
+        let column = |index: usize| {
+            (m[index] * m[index] + m[index + 3] * m[index + 3] + m[index + 6] * m[index + 6]).sqrt()
+        };
+        let bound = column(0) * column(1) * column(2);
+
+        if determinant.abs() <= f64::EPSILON * bound
+        {
+            return None;
+        }
+
+        let factor = 1.0 / determinant;
+
+        Some(Self::new([
+            (m[4] * m[8] - m[5] * m[7]) * factor,
+            (m[2] * m[7] - m[1] * m[8]) * factor,
+            (m[1] * m[5] - m[2] * m[4]) * factor,
+            (m[5] * m[6] - m[3] * m[8]) * factor,
+            (m[0] * m[8] - m[2] * m[6]) * factor,
+            (m[2] * m[3] - m[0] * m[5]) * factor,
+            (m[3] * m[7] - m[4] * m[6]) * factor,
+            (m[1] * m[6] - m[0] * m[7]) * factor,
+            (m[0] * m[4] - m[1] * m[3]) * factor,
+        ]))
+    }
+
+    pub fn transform_point(&self, point: Point2f) -> Point2f
+    {
+        let m = &self.data;
+
+        let nx = m[0] * point.x + m[1] * point.y + m[2];
+        let ny = m[3] * point.x + m[4] * point.y + m[5];
+        let w = m[6] * point.x + m[7] * point.y + m[8];
+
+        if w != 0.0
+        {
+            Point2f::new(nx / w, ny / w)
+        }
+        else
+        {
+            Point2f::new(nx, ny)
+        }
+    }
+
+    /// Transforms a direction, the translation part of the matrix is ignored.
+    pub fn transform_vector(&self, vector: Vec2f) -> Vec2f
+    {
+        let m = &self.data;
+
+        Vec2f::new(
+            m[0] * vector.x + m[1] * vector.y,
+            m[3] * vector.x + m[4] * vector.y,
+        )
+    }
+
+    // Moves the pivot of the transformation from the origin to the `point`.
+    fn around(self, point: Point2f) -> Self
+    {
+        let offset = point.to_vec2f();
+
+        Self::translation(offset) * self * Self::translation(offset.opposite())
+    }
 }
 
 impl Mul<Matrix3> for Matrix3
@@ -75,3 +233,139 @@ impl MulAssign<Matrix3> for Matrix3
         *self = *self * rhs;
     }
 }
+
+impl Mul<Point2f> for Matrix3
+{
+    type Output = Point2f;
+
+    fn mul(self, rhs: Point2f) -> Self::Output
+    {
+        self.transform_point(rhs)
+    }
+}
+
+impl Mul<Vec2f> for Matrix3
+{
+    type Output = Vec2f;
+
+    fn mul(self, rhs: Vec2f) -> Self::Output
+    {
+        self.transform_vector(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_transforms_to(matrix: Matrix3, from: Point2f, to: Point2f)
+    {
+        let result = matrix * from;
+
+        assert!((result.x - to.x).abs() < 1e-9, "{result:?} != {to:?}");
+        assert!((result.y - to.y).abs() < 1e-9, "{result:?} != {to:?}");
+    }
+
+    #[test]
+    fn scaling_around_keeps_pivot_in_place()
+    {
+        let pivot = Point2f::new(5.0, 5.0);
+        let matrix = Matrix3::scaling_around(pivot, Vec2f::new(2.0, 3.0));
+
+        assert_transforms_to(matrix, pivot, pivot);
+        assert_transforms_to(matrix, Point2f::new(6.0, 6.0), Point2f::new(7.0, 8.0));
+    }
+
+    #[test]
+    fn shearing_moves_points_along_axes()
+    {
+        let matrix = Matrix3::shearing(Vec2f::new(1.0, 0.0));
+
+        assert_transforms_to(matrix, Point2f::new(0.0, 2.0), Point2f::new(2.0, 2.0));
+        assert_transforms_to(matrix, Point2f::new(3.0, 0.0), Point2f::new(3.0, 0.0));
+    }
+
+    #[test]
+    fn reflection_across_diagonal_swaps_coordinates()
+    {
+        let matrix = Matrix3::reflection(Vec2f::new(1.0, 1.0));
+
+        assert_transforms_to(matrix, Point2f::new(1.0, 4.0), Point2f::new(4.0, 1.0));
+    }
+
+    #[test]
+    fn reflection_around_point_mirrors_across_vertical_line()
+    {
+        let matrix = Matrix3::reflection_around(Point2f::new(5.0, 0.0), Vec2f::new(0.0, 1.0));
+
+        assert_transforms_to(matrix, Point2f::new(3.0, 7.0), Point2f::new(7.0, 7.0));
+    }
+
+    #[test]
+    fn determinant_of_composed_transformations()
+    {
+        let rotation = Matrix3::rotation_around(Point2f::new(1.0, 2.0), 33.0);
+        let scaling = Matrix3::scaling(Vec2f::new(2.0, 3.0));
+        let reflection = Matrix3::reflection(Vec2f::new(1.0, 0.0));
+
+        assert!(((rotation * scaling).determinant() - 6.0).abs() < 1e-9);
+        assert!((reflection.determinant() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn inverse_undoes_the_transformation()
+    {
+        let matrix = Matrix3::translation(Vec2f::new(3.0, -2.0))
+            * Matrix3::rotation(30.0)
+            * Matrix3::scaling(Vec2f::new(2.0, 0.5));
+        let inverse = matrix.inverse().unwrap();
+        let point = Point2f::new(7.0, 11.0);
+
+        assert_transforms_to(inverse * matrix, point, point);
+        assert_transforms_to(inverse, matrix * point, point);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse()
+    {
+        let matrix = Matrix3::scaling(Vec2f::new(0.0, 1.0));
+
+        assert!(matrix.inverse().is_none());
+    }
+
+    #[test]
+    fn singularity_does_not_depend_on_the_scale()
+    {
+        let tiny = Matrix3::scaling(Vec2f::new(1e-9, 1e-9)) * Matrix3::rotation(30.0);
+        let point = Point2f::new(3.0, -4.0);
+
+        assert_transforms_to(tiny.inverse().unwrap(), tiny * point, point);
+
+        let almost_singular = Matrix3::new([1e8, 1e8, 0.0, 1e8, 1e8 + 1e-8, 0.0, 0.0, 0.0, 1.0]);
+
+        assert!(almost_singular.inverse().is_none());
+    }
+
+    #[test]
+    fn transform_vector_ignores_translation()
+    {
+        let matrix = Matrix3::translation(Vec2f::new(5.0, 5.0)) * Matrix3::rotation(90.0);
+        let vector = matrix * Vec2f::new(1.0, 0.0);
+
+        assert!((vector - Vec2f::new(0.0, 1.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn decompose_recovers_the_components()
+    {
+        let matrix = Matrix3::translation(Vec2f::new(4.0, -1.0))
+            * Matrix3::rotation(-60.0)
+            * Matrix3::scaling(Vec2f::new(3.0, 2.0));
+        let decomposed = matrix.decompose();
+
+        assert!((decomposed.translation - Vec2f::new(4.0, -1.0)).length() < 1e-9);
+        assert!((decomposed.rotation + 60.0).abs() < 1e-9);
+        assert!((decomposed.scale - Vec2f::new(3.0, 2.0)).length() < 1e-9);
+    }
+}
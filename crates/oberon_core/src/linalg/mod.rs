@@ -10,6 +10,9 @@ pub use point2::Point2;
 mod point2f;
 pub use point2f::Point2f;
 
+mod transform2d;
+pub use transform2d::Transform2D;
+
 mod vec2;
 pub use vec2::Vec2;
 
@@ -1,8 +1,8 @@
-use std::ops::{Add, AddAssign};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 use crate::linalg::{Matrix3, Point2f, Vec2};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Point2
 {
     pub x: usize,
@@ -26,11 +26,21 @@ impl Point2
         }
     }
 
+    pub fn distance(&self, other: Point2) -> f64
+    {
+        (other - *self).length()
+    }
+
     pub const fn to_point2f(&self) -> Point2f
     {
         Point2f::new(self.x as f64, self.y as f64)
     }
 
+    pub const fn to_vec2(&self) -> Vec2
+    {
+        Vec2::from_signed(self.x, self.y)
+    }
+
     pub fn transform(&self, transform: &Matrix3) -> Self
     {
         let m = &transform.data;
@@ -77,3 +87,59 @@ impl AddAssign<Vec2> for Point2
         self.y = self.y.saturating_add_signed(rhs.y);
     }
 }
+
+impl Sub<Vec2> for Point2
+{
+    type Output = Point2;
+
+    fn sub(self, rhs: Vec2) -> Self::Output
+    {
+        self + -rhs
+    }
+}
+
+impl SubAssign<Vec2> for Point2
+{
+    fn sub_assign(&mut self, rhs: Vec2)
+    {
+        *self += -rhs;
+    }
+}
+
+impl Sub<Point2> for Point2
+{
+    type Output = Vec2;
+
+    fn sub(self, rhs: Point2) -> Self::Output
+    {
+        self.to_vec2() - rhs.to_vec2()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn points_saturate_at_the_origin()
+    {
+        let mut point = Point2::new(2, 3) - Vec2::new(5, 1);
+
+        assert_eq!(point, Point2::new(0, 2));
+
+        point -= Vec2::new(-1, 1);
+
+        assert_eq!(point, Point2::new(1, 1));
+    }
+
+    #[test]
+    fn difference_of_points_is_signed()
+    {
+        let a = Point2::new(1, 5);
+        let b = Point2::new(4, 1);
+
+        assert_eq!(a - b, Vec2::new(-3, 4));
+        assert_eq!(a.distance(b), 5.0);
+    }
+}
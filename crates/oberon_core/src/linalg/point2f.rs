@@ -1,6 +1,8 @@
-use crate::linalg::Vec2f;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-#[derive(Copy, Clone, Debug)]
+use crate::linalg::{Matrix3, Point2, Vec2, Vec2f};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Point2f
 {
     pub x: f64,
@@ -9,13 +11,159 @@ pub struct Point2f
 
 impl Point2f
 {
+    pub const ZERO: Self = Self::new(0.0, 0.0);
+
     pub const fn new(x: f64, y: f64) -> Self
     {
         Self { x, y }
     }
 
+    pub fn distance(&self, other: Point2f) -> f64
+    {
+        (other - *self).length()
+    }
+
+    pub fn distance_squared(&self, other: Point2f) -> f64
+    {
+        (other - *self).length_squared()
+    }
+
+    pub fn lerp(&self, other: Point2f, t: f64) -> Self
+    {
+        *self + (other - *self) * t
+    }
+
+    pub fn midpoint(&self, other: Point2f) -> Self
+    {
+        self.lerp(other, 0.5)
+    }
+
+    /// Rotates the point around the pivot by the angle given in degrees.
+    pub fn rotate_around(&self, pivot: Point2f, angle: f64) -> Self
+    {
+        pivot + (*self - pivot).rotate(angle)
+    }
+
+    /// Rounds the point to the closest cell, negative coordinates are clamped to zero.
+    pub fn to_nearest_point2(&self) -> Point2
+    {
+        let nearest = self.to_nearest_vec2();
+        Point2::from_signed(nearest.x, nearest.y)
+    }
+
+    /// Rounds the point to the closest signed cell coordinates.
+    pub fn to_nearest_vec2(&self) -> Vec2
+    {
+        Vec2::new(self.x.round() as isize, self.y.round() as isize)
+    }
+
     pub const fn to_vec2f(&self) -> Vec2f
     {
         Vec2f::new(self.x, self.y)
     }
+
+    pub fn transform(&self, transform: &Matrix3) -> Self
+    {
+        transform.transform_point(*self)
+    }
+}
+
+impl From<Point2> for Point2f
+{
+    fn from(value: Point2) -> Self
+    {
+        value.to_point2f()
+    }
+}
+
+impl Add<Vec2f> for Point2f
+{
+    type Output = Point2f;
+
+    fn add(self, rhs: Vec2f) -> Self::Output
+    {
+        Point2f::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vec2f> for Point2f
+{
+    fn add_assign(&mut self, rhs: Vec2f)
+    {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub<Vec2f> for Point2f
+{
+    type Output = Point2f;
+
+    fn sub(self, rhs: Vec2f) -> Self::Output
+    {
+        Point2f::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vec2f> for Point2f
+{
+    fn sub_assign(&mut self, rhs: Vec2f)
+    {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Sub<Point2f> for Point2f
+{
+    type Output = Vec2f;
+
+    fn sub(self, rhs: Point2f) -> Self::Output
+    {
+        Vec2f::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn points_and_vectors_arithmetic()
+    {
+        let mut point = Point2f::new(1.0, 1.0) + Vec2f::new(2.0, 3.0);
+
+        assert_eq!(point, Point2f::new(3.0, 4.0));
+        assert_eq!(point - Point2f::ZERO, Vec2f::new(3.0, 4.0));
+        assert_eq!(point.distance(Point2f::ZERO), 5.0);
+
+        point -= Vec2f::new(3.0, 0.0);
+
+        assert_eq!(point, Point2f::new(0.0, 4.0));
+    }
+
+    #[test]
+    fn interpolation_and_rotation()
+    {
+        let a = Point2f::new(0.0, 0.0);
+        let b = Point2f::new(4.0, -2.0);
+
+        assert_eq!(a.midpoint(b), Point2f::new(2.0, -1.0));
+        assert_eq!(a.lerp(b, 0.25), Point2f::new(1.0, -0.5));
+
+        let rotated = Point2f::new(2.0, 1.0).rotate_around(Point2f::new(1.0, 1.0), 90.0);
+
+        assert!(rotated.distance(Point2f::new(1.0, 2.0)) < 1e-9);
+    }
+
+    #[test]
+    fn conversions_to_cells()
+    {
+        let point = Point2f::new(-1.4, 2.6);
+
+        assert_eq!(point.to_nearest_vec2(), Vec2::new(-1, 3));
+        assert_eq!(point.to_nearest_point2(), Point2::new(0, 3));
+        assert_eq!(Point2f::from(Point2::new(1, 2)), Point2f::new(1.0, 2.0));
+    }
 }
@@ -0,0 +1,214 @@
+use crate::linalg::algorithms::GridCells;
+use crate::linalg::{Point2f, Vec2, Vec2f};
+
+/// Axis aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb
+{
+    pub min: Point2f,
+    pub max: Point2f,
+}
+
+impl Aabb
+{
+    pub const fn new(min: Point2f, max: Point2f) -> Self
+    {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Point2f, half_extents: Vec2f) -> Self
+    {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// Smallest box enclosing all of the points. The box is empty when there are no points.
+    pub fn from_points(points: &[Point2f]) -> Self
+    {
+        let (min_x, min_y, max_x, max_y) = points.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(min_x, min_y, max_x, max_y), point| {
+                (
+                    min_x.min(point.x),
+                    min_y.min(point.y),
+                    max_x.max(point.x),
+                    max_y.max(point.y),
+                )
+            },
+        );
+        Self::new(Point2f::new(min_x, min_y), Point2f::new(max_x, max_y))
+    }
+
+    pub fn area(&self) -> f64
+    {
+        self.width() * self.height()
+    }
+
+    pub fn center(&self) -> Point2f
+    {
+        self.min.midpoint(self.max)
+    }
+
+    pub fn width(&self) -> f64
+    {
+        (self.max.x - self.min.x).max(0.0)
+    }
+
+    pub fn height(&self) -> f64
+    {
+        (self.max.y - self.min.y).max(0.0)
+    }
+
+    pub fn size(&self) -> Vec2f
+    {
+        Vec2f::new(self.width(), self.height())
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.min.x > self.max.x || self.min.y > self.max.y
+    }
+
+    /// Distance from the point to the closest point of the box, zero when the point is inside.
+    pub fn distance_to(&self, point: Point2f) -> f64
+    {
+        let dx = (self.min.x - point.x).max(point.x - self.max.x).max(0.0);
+        let dy = (self.min.y - point.y).max(point.y - self.max.y).max(0.0);
+
+        dx.hypot(dy)
+    }
+
+    pub fn contains(&self, other: &Aabb) -> bool
+    {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.max.x >= other.max.x
+            && self.max.y >= other.max.y
+    }
+
+    pub fn contains_point(&self, point: Point2f) -> bool
+    {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Grows the box by the `margin` in every direction, negative margin shrinks it.
+    pub fn expand(&self, margin: f64) -> Self
+    {
+        self.expand_xy(Vec2f::new(margin, margin))
+    }
+
+    pub fn expand_xy(&self, margin: Vec2f) -> Self
+    {
+        Self::new(self.min - margin, self.max + margin)
+    }
+
+    pub fn intersection(&self, other: &Aabb) -> Option<Self>
+    {
+        let min = Point2f::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point2f::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+
+        let intersection = Self::new(min, max);
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// Touching boxes overlap as well.
+    pub fn overlaps(&self, other: &Aabb) -> bool
+    {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self
+    {
+        let min = Point2f::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y));
+        let max = Point2f::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y));
+
+        Self::new(min, max)
+    }
+
+    /// Every grid cell touched by the box, in signed cell coordinates.
+    pub fn cells(&self) -> GridCells
+    {
+        GridCells::new(
+            Vec2::new(self.min.x.floor() as isize, self.min.y.floor() as isize),
+            Vec2::new(self.max.x.ceil() as isize, self.max.y.ceil() as isize),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn aabb(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Aabb
+    {
+        Aabb::new(Point2f::new(min_x, min_y), Point2f::new(max_x, max_y))
+    }
+
+    #[test]
+    fn from_points_encloses_all_of_them()
+    {
+        let points = [
+            Point2f::new(1.0, 5.0),
+            Point2f::new(-2.0, 3.0),
+            Point2f::new(4.0, -1.0),
+        ];
+
+        assert_eq!(Aabb::from_points(&points), aabb(-2.0, -1.0, 4.0, 5.0));
+        assert!(Aabb::from_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn union_and_intersection_of_overlapping_boxes()
+    {
+        let first = aabb(0.0, 0.0, 4.0, 4.0);
+        let second = aabb(2.0, 1.0, 6.0, 3.0);
+
+        assert_eq!(first.union(&second), aabb(0.0, 0.0, 6.0, 4.0));
+        assert_eq!(first.intersection(&second), Some(aabb(2.0, 1.0, 4.0, 3.0)));
+        assert!(first.overlaps(&second));
+    }
+
+    #[test]
+    fn disjoint_boxes_have_no_intersection()
+    {
+        let first = aabb(0.0, 0.0, 1.0, 1.0);
+        let second = aabb(2.0, 2.0, 3.0, 3.0);
+
+        assert!(first.intersection(&second).is_none());
+        assert!(!first.overlaps(&second));
+    }
+
+    #[test]
+    fn contains_boxes_and_points()
+    {
+        let outer = aabb(0.0, 0.0, 10.0, 10.0);
+        let inner = aabb(2.0, 2.0, 3.0, 3.0);
+
+        assert!(outer.contains(&inner));
+        assert!(!inner.contains(&outer));
+        assert!(outer.contains_point(Point2f::new(10.0, 0.0)));
+        assert!(!outer.contains_point(Point2f::new(10.5, 0.0)));
+    }
+
+    #[test]
+    fn expand_grows_in_every_direction()
+    {
+        let expanded = aabb(1.0, 1.0, 2.0, 3.0).expand(1.0);
+
+        assert_eq!(expanded, aabb(0.0, 0.0, 3.0, 4.0));
+        assert_eq!(expanded.area(), 12.0);
+    }
+
+    #[test]
+    fn cells_cover_fractional_bounds()
+    {
+        let cells = aabb(-0.5, 0.5, 1.0, 1.5).cells().collect::<Vec<_>>();
+
+        assert_eq!(cells.len(), 9);
+        assert_eq!((cells[0].x, cells[0].y), (-1, 0));
+        assert_eq!((cells[8].x, cells[8].y), (1, 2));
+    }
+}
@@ -0,0 +1,389 @@
+use crate::linalg::{Point2f, Vec2f};
+
+pub(crate) const EPSILON: f64 = 1e-9;
+
+/// Result of an intersection query between two shapes.
+#[derive(Clone, Debug)]
+pub struct Contact
+{
+    /// Unit vector pointing from the first shape towards the second one.
+    pub normal: Vec2f,
+    /// How deep the shapes overlap along the `normal`.
+    pub depth: f64,
+    /// Points where the shapes touch, there are at most two of them.
+    pub points: Vec<Point2f>,
+}
+
+impl Contact
+{
+    /// The smallest translation moving the second shape out of the first one. Moving the first
+    /// shape by the opposite vector separates them as well.
+    pub fn penetration(&self) -> Vec2f
+    {
+        self.normal * self.depth
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ray
+{
+    pub origin: Point2f,
+    pub direction: Vec2f,
+}
+
+impl Ray
+{
+    pub fn new(origin: Point2f, direction: Vec2f) -> Self
+    {
+        let direction = direction.normalize();
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, distance: f64) -> Point2f
+    {
+        self.origin + self.direction * distance
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit
+{
+    pub point: Point2f,
+    /// Unit normal of the hit edge, facing against the ray.
+    pub normal: Vec2f,
+    pub distance: f64,
+}
+
+#[derive(Debug)]
+struct Edge
+{
+    deepest: Point2f,
+    from: Point2f,
+    to: Point2f,
+}
+
+impl Edge
+{
+    fn direction(&self) -> Vec2f
+    {
+        self.to - self.from
+    }
+}
+
+// Source: https://dyn4j.org/2010/01/sat/
+pub(crate) fn collide(first: &[Point2f], second: &[Point2f]) -> Option<Contact>
+{
+    let mut normal = Vec2f::ZEROES;
+    let mut depth = f64::MAX;
+
+    for axis in edge_normals(first).chain(edge_normals(second))
+    {
+        let (first_min, first_max) = project(first, axis);
+        let (second_min, second_max) = project(second, axis);
+
+        let overlap = first_max.min(second_max) - first_min.max(second_min);
+
+        if overlap <= 0.0
+        {
+            return None;
+        }
+        if overlap < depth
+        {
+            depth = overlap;
+            normal = axis;
+        }
+    }
+
+    if (centroid(second) - centroid(first)).dot(normal) < 0.0
+    {
+        normal = normal.opposite();
+    }
+
+    let points = contact_points(first, second, normal);
+
+    Some(Contact {
+        normal,
+        depth,
+        points,
+    })
+}
+
+pub(crate) fn raycast(vertices: &[Point2f], ray: &Ray) -> Option<RayHit>
+{
+    let mut closest: Option<RayHit> = None;
+
+    for index in 0..vertices.len()
+    {
+        let from = vertices[index];
+        let to = vertices[(index + 1) % vertices.len()];
+        let edge = to - from;
+
+        let denominator = ray.direction.cross(edge);
+
+        if denominator.abs() < EPSILON
+        {
+            continue;
+        }
+
+        let to_edge = from - ray.origin;
+        let distance = to_edge.cross(edge) / denominator;
+        let along_edge = to_edge.cross(ray.direction) / denominator;
+
+        if distance < 0.0 || !(0.0..=1.0).contains(&along_edge)
+        {
+            continue;
+        }
+        if closest.is_some_and(|hit| hit.distance <= distance)
+        {
+            continue;
+        }
+
+        let mut normal = edge.perpendicular().normalize();
+
+        if normal.dot(ray.direction) > 0.0
+        {
+            normal = normal.opposite();
+        }
+
+        closest = Some(RayHit {
+            point: ray.at(distance),
+            normal,
+            distance,
+        });
+    }
+    closest
+}
+
+// Source: https://dyn4j.org/2011/11/contact-points-using-clipping/
+fn contact_points(first: &[Point2f], second: &[Point2f], normal: Vec2f) -> Vec<Point2f>
+{
+    let first_edge = best_edge(first, normal);
+    let second_edge = best_edge(second, normal.opposite());
+
+    let first_alignment = first_edge.direction().normalize().dot(normal).abs();
+    let second_alignment = second_edge.direction().normalize().dot(normal).abs();
+
+    // The edge more perpendicular to the normal is the reference one, the other gets clipped.
+    let (reference, incident, reference_normal) = if first_alignment <= second_alignment
+    {
+        (first_edge, second_edge, normal)
+    }
+    else
+    {
+        (second_edge, first_edge, normal.opposite())
+    };
+
+    let direction = reference.direction().normalize();
+
+    let lower = direction.dot(reference.from.to_vec2f());
+    let clipped = clip(incident.from, incident.to, direction, lower);
+
+    if clipped.len() < 2
+    {
+        return vec![reference.deepest];
+    }
+
+    let upper = direction.dot(reference.to.to_vec2f());
+    let clipped = clip(clipped[0], clipped[1], direction.opposite(), -upper);
+
+    if clipped.len() < 2
+    {
+        return vec![reference.deepest];
+    }
+
+    let face = reference_normal.dot(reference.deepest.to_vec2f());
+
+    clipped
+        .into_iter()
+        .filter(|point| reference_normal.dot(point.to_vec2f()) <= face + EPSILON)
+        .collect()
+}
+
+fn best_edge(vertices: &[Point2f], normal: Vec2f) -> Edge
+{
+    let count = vertices.len();
+
+    let index = (0..count)
+        .max_by(|&a, &b| {
+            let a = normal.dot(vertices[a].to_vec2f());
+            let b = normal.dot(vertices[b].to_vec2f());
+            a.total_cmp(&b)
+        })
+        .unwrap_or(0);
+
+    let deepest = vertices[index];
+    let next = vertices[(index + 1) % count];
+    let prev = vertices[(index + count - 1) % count];
+
+    let left = (deepest - next).normalize();
+    let right = (deepest - prev).normalize();
+
+    if right.dot(normal).abs() <= left.dot(normal).abs()
+    {
+        Edge {
+            deepest,
+            from: prev,
+            to: deepest,
+        }
+    }
+    else
+    {
+        Edge {
+            deepest,
+            from: deepest,
+            to: next,
+        }
+    }
+}
+
+// Keeps the part of the segment lying in front of the plane given by its normal and offset.
+fn clip(from: Point2f, to: Point2f, normal: Vec2f, offset_along: f64) -> Vec<Point2f>
+{
+    let mut points = Vec::with_capacity(2);
+
+    let from_distance = normal.dot(from.to_vec2f()) - offset_along;
+    let to_distance = normal.dot(to.to_vec2f()) - offset_along;
+
+    if from_distance >= 0.0
+    {
+        points.push(from);
+    }
+    if to_distance >= 0.0
+    {
+        points.push(to);
+    }
+    if from_distance * to_distance < 0.0
+    {
+        let ratio = from_distance / (from_distance - to_distance);
+        points.push(from.lerp(to, ratio));
+    }
+    points
+}
+
+fn edge_normals(vertices: &[Point2f]) -> impl Iterator<Item = Vec2f> + '_
+{
+    (0..vertices.len()).filter_map(|index| {
+        let edge = vertices[(index + 1) % vertices.len()] - vertices[index];
+
+        (edge.length() > EPSILON).then(|| edge.perpendicular().normalize())
+    })
+}
+
+pub(crate) fn project(vertices: &[Point2f], axis: Vec2f) -> (f64, f64)
+{
+    vertices
+        .iter()
+        .map(|vertex| axis.dot(vertex.to_vec2f()))
+        .fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+fn centroid(vertices: &[Point2f]) -> Point2f
+{
+    let sum = vertices
+        .iter()
+        .fold(Vec2f::ZEROES, |sum, vertex| sum + vertex.to_vec2f());
+
+    (sum / vertices.len() as f64).to_point2f()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::linalg::shapes::{Rectangle, Shape, Triangle};
+
+    fn square(x: f64, y: f64, size: f64) -> Rectangle
+    {
+        Rectangle::from_corner_and_size(Point2f::new(x, y), Vec2f::new(size, size))
+    }
+
+    fn assert_close(a: f64, b: f64)
+    {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    #[test]
+    fn separated_shapes_do_not_collide()
+    {
+        let first = square(0.0, 0.0, 2.0);
+        let second = square(5.0, 0.0, 2.0);
+
+        assert!(first.collide(&second).is_none());
+        assert!(!second.intersects(&first));
+    }
+
+    #[test]
+    fn overlapping_squares_share_an_edge_contact()
+    {
+        let first = square(0.0, 0.0, 4.0);
+        let second = square(3.0, 1.0, 2.0);
+
+        let contact = first.collide(&second).unwrap();
+
+        assert_close(contact.depth, 1.0);
+        assert_close(contact.normal.x, 1.0);
+        assert_close(contact.normal.y, 0.0);
+        assert_close(contact.penetration().x, 1.0);
+        assert_eq!(contact.points.len(), 2);
+
+        for point in contact.points
+        {
+            assert_close(point.x, 3.0);
+        }
+    }
+
+    #[test]
+    fn normal_points_from_first_to_second_shape()
+    {
+        let first = square(3.0, 1.0, 2.0);
+        let second = square(0.0, 0.0, 4.0);
+
+        let contact = first.collide(&second).unwrap();
+
+        assert_close(contact.normal.x, -1.0);
+    }
+
+    #[test]
+    fn vertex_pushed_into_a_face_gives_single_contact()
+    {
+        let first = square(0.0, 0.0, 4.0);
+        let second = Triangle::from_vertices([
+            Point2f::new(2.0, 3.5),
+            Point2f::new(0.0, 6.0),
+            Point2f::new(4.0, 6.0),
+        ]);
+
+        let contact = first.collide(&second).unwrap();
+
+        assert_close(contact.depth, 0.5);
+        assert_close(contact.normal.y, 1.0);
+        assert_eq!(contact.points.len(), 1);
+        assert_close(contact.points[0].x, 2.0);
+        assert_close(contact.points[0].y, 3.5);
+    }
+
+    #[test]
+    fn raycast_hits_the_closest_edge()
+    {
+        let shape = square(5.0, 0.0, 2.0);
+        let ray = Ray::new(Point2f::new(0.0, 1.0), Vec2f::new(2.0, 0.0));
+
+        let hit = shape.raycast(&ray).unwrap();
+
+        assert_close(hit.distance, 5.0);
+        assert_close(hit.point.x, 5.0);
+        assert_close(hit.point.y, 1.0);
+        assert_close(hit.normal.x, -1.0);
+    }
+
+    #[test]
+    fn raycast_misses_shape_behind_the_origin()
+    {
+        let shape = square(5.0, 0.0, 2.0);
+        let ray = Ray::new(Point2f::new(10.0, 1.0), Vec2f::new(1.0, 0.0));
+
+        assert!(shape.raycast(&ray).is_none());
+    }
+}
@@ -1,5 +1,11 @@
-mod bounding_box;
-pub use bounding_box::BoundingBox;
+mod aabb;
+pub use aabb::Aabb;
+
+mod collision;
+pub use collision::{Contact, Ray, RayHit};
+
+mod obb;
+pub use obb::Obb;
 
 mod polygon;
 pub use polygon::ConvexPolygon;
@@ -0,0 +1,234 @@
+use crate::linalg::shapes::{collision, Aabb};
+use crate::linalg::{Point2f, Vec2f};
+
+/// Oriented bounding box, the rotation is given in degrees like in the rest of the linalg module.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb
+{
+    pub center: Point2f,
+    pub half_extents: Vec2f,
+    pub rotation: f64,
+}
+
+impl Obb
+{
+    pub const fn new(center: Point2f, half_extents: Vec2f, rotation: f64) -> Self
+    {
+        Self {
+            center,
+            half_extents,
+            rotation,
+        }
+    }
+
+    pub fn from_aabb(aabb: &Aabb) -> Self
+    {
+        Self::new(aabb.center(), aabb.size() / 2.0, 0.0)
+    }
+
+    /// The smallest box enclosing a convex polygon, the vertices have to be given in order.
+    // Source: https://en.wikipedia.org/wiki/Minimum_bounding_box_algorithms
+    pub fn enclosing(vertices: &[Point2f]) -> Self
+    {
+        let mut best = Self::from_aabb(&Aabb::from_points(vertices));
+        let mut best_area = best.area();
+
+        for index in 0..vertices.len()
+        {
+            let edge = vertices[(index + 1) % vertices.len()] - vertices[index];
+
+            if edge.length_squared() == 0.0
+            {
+                continue;
+            }
+
+            let candidate = Self::along_axis(vertices, edge.normalize());
+            let area = candidate.area();
+
+            if area < best_area
+            {
+                best = candidate;
+                best_area = area;
+            }
+        }
+        best
+    }
+
+    pub fn area(&self) -> f64
+    {
+        4.0 * self.half_extents.x * self.half_extents.y
+    }
+
+    pub fn axes(&self) -> (Vec2f, Vec2f)
+    {
+        let axis = Vec2f::from_angle(self.rotation);
+        (axis, axis.perpendicular())
+    }
+
+    /// Corners in the clockwise order on screen, starting from the top left one.
+    pub fn corners(&self) -> [Point2f; 4]
+    {
+        let (u, v) = self.axes();
+        let u = u * self.half_extents.x;
+        let v = v * self.half_extents.y;
+
+        let corner = |su: f64, sv: f64| self.center + u * su + v * sv;
+        [
+            corner(-1.0, -1.0),
+            corner(1.0, -1.0),
+            corner(1.0, 1.0),
+            corner(-1.0, 1.0),
+        ]
+    }
+
+    pub fn contains(&self, other: &Obb) -> bool
+    {
+        other
+            .corners()
+            .iter()
+            .all(|corner| self.contains_point(*corner))
+    }
+
+    pub fn contains_point(&self, point: Point2f) -> bool
+    {
+        let (u, v) = self.axes();
+        let offset = point - self.center;
+
+        let along_u = offset.dot(u);
+        let along_v = offset.dot(v);
+
+        along_u.abs() <= self.half_extents.x && along_v.abs() <= self.half_extents.y
+    }
+
+    /// Grows the box by the `margin` in every direction, negative margin shrinks it.
+    pub fn expand(&self, margin: f64) -> Self
+    {
+        let half_extents = Vec2f::new(
+            (self.half_extents.x + margin).max(0.0),
+            (self.half_extents.y + margin).max(0.0),
+        );
+        Self::new(self.center, half_extents, self.rotation)
+    }
+
+    /// Touching boxes overlap as well, like the `Aabb` ones, while `Shape::collide` reports only
+    /// the shapes which penetrate each other.
+    pub fn overlaps(&self, other: &Obb) -> bool
+    {
+        let (first, second) = (self.corners(), other.corners());
+        let (u, v) = self.axes();
+        let (s, t) = other.axes();
+
+        [u, v, s, t].into_iter().all(|axis| {
+            let (min_first, max_first) = collision::project(&first, axis);
+            let (min_second, max_second) = collision::project(&second, axis);
+
+            max_first >= min_second - collision::EPSILON
+                && max_second >= min_first - collision::EPSILON
+        })
+    }
+
+    pub fn to_aabb(&self) -> Aabb
+    {
+        Aabb::from_points(&self.corners())
+    }
+
+    fn along_axis(vertices: &[Point2f], axis: Vec2f) -> Self
+    {
+        let normal = axis.perpendicular();
+
+        let (min_u, max_u, min_v, max_v) = vertices.iter().fold(
+            (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+            |(min_u, max_u, min_v, max_v), vertex| {
+                let u = vertex.to_vec2f().dot(axis);
+                let v = vertex.to_vec2f().dot(normal);
+
+                (min_u.min(u), max_u.max(u), min_v.min(v), max_v.max(v))
+            },
+        );
+        let mid_u = (min_u + max_u) / 2.0;
+        let mid_v = (min_v + max_v) / 2.0;
+
+        let center = (axis * mid_u + normal * mid_v).to_point2f();
+        let half_extents = Vec2f::new(max_u - min_u, max_v - min_v) / 2.0;
+        let rotation = axis.angle();
+
+        Self::new(center, half_extents, rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_close(a: f64, b: f64)
+    {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn enclosing_box_follows_rotated_square()
+    {
+        let diamond = [
+            Point2f::new(0.0, 2.0),
+            Point2f::new(2.0, 0.0),
+            Point2f::new(4.0, 2.0),
+            Point2f::new(2.0, 4.0),
+        ];
+        let obb = Obb::enclosing(&diamond);
+
+        assert_close(obb.area(), 8.0);
+        assert_close(obb.center.x, 2.0);
+        assert_close(obb.center.y, 2.0);
+        assert_eq!(Aabb::from_points(&diamond).area(), 16.0);
+    }
+
+    #[test]
+    fn rotated_box_contains_points_along_its_axes()
+    {
+        let obb = Obb::new(Point2f::ZERO, Vec2f::new(2.0, 0.5), 45.0);
+
+        assert!(obb.contains_point(Point2f::new(1.0, 1.0)));
+        assert!(!obb.contains_point(Point2f::new(1.0, -1.0)));
+        assert!(obb.contains(&Obb::new(Point2f::ZERO, Vec2f::new(1.0, 0.25), 45.0)));
+    }
+
+    #[test]
+    fn overlap_of_rotated_boxes()
+    {
+        let first = Obb::new(Point2f::ZERO, Vec2f::new(2.0, 0.5), 45.0);
+        let second = Obb::new(Point2f::new(2.0, 2.0), Vec2f::new(1.0, 1.0), 0.0);
+        let third = Obb::new(Point2f::new(2.0, -2.0), Vec2f::new(1.0, 1.0), 0.0);
+
+        assert!(first.overlaps(&second));
+        assert!(!first.overlaps(&third));
+    }
+
+    #[test]
+    fn touching_boxes_overlap()
+    {
+        let first = Obb::new(Point2f::ZERO, Vec2f::new(1.0, 1.0), 0.0);
+        let beside = Obb::new(Point2f::new(2.0, 0.5), Vec2f::new(1.0, 1.0), 0.0);
+        let corner = Obb::new(Point2f::new(2.0, 2.0), Vec2f::ONES, 90.0);
+        let half = 2.0_f64.sqrt() / 2.0;
+        let rotated = Obb::new(Point2f::new(2.0, 0.0), Vec2f::new(half, half), 45.0);
+        let apart = Obb::new(Point2f::new(2.1, 0.0), Vec2f::new(1.0, 1.0), 0.0);
+
+        assert!(first.overlaps(&beside));
+        assert!(beside.overlaps(&first));
+        assert!(first.overlaps(&corner));
+        assert!(first.overlaps(&rotated));
+        assert!(!first.overlaps(&apart));
+        assert!(first.to_aabb().overlaps(&beside.to_aabb()));
+    }
+
+    #[test]
+    fn expand_and_convert_to_aabb()
+    {
+        let obb = Obb::new(Point2f::new(1.0, 1.0), Vec2f::new(1.0, 1.0), 0.0).expand(1.0);
+        let aabb = obb.to_aabb();
+
+        assert_close(aabb.min.x, -1.0);
+        assert_close(aabb.max.y, 3.0);
+    }
+}
@@ -1,34 +1,41 @@
 use crate::linalg::algorithms::Bresenham;
-use crate::linalg::shapes::{BoundingBox, LazyShape, LazyTransformer, Shape};
-use crate::linalg::{Matrix3, Point2, Point2f};
+use crate::linalg::shapes::{Aabb, LazyShape, LazyTransformer, Shape};
+use crate::linalg::{Matrix3, Point2f, Vec2};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ConvexPolygon<const N: usize>
 {
-    vertices_curr: [Point2; N],
-    vertices_orig: [Point2; N],
+    vertices_curr: [Point2f; N],
+    vertices_orig: [Point2f; N],
+    deformations: Matrix3,
     rotations: Matrix3,
     translations: Matrix3,
 }
 
 impl<const N: usize> ConvexPolygon<N>
 {
-    pub fn from_vertices(vertices: [Point2; N]) -> Self
+    pub fn from_vertices(vertices: [Point2f; N]) -> Self
     {
         Self {
             vertices_curr: vertices,
             vertices_orig: vertices,
+            deformations: Matrix3::IDENTITY,
             rotations: Matrix3::IDENTITY,
             translations: Matrix3::IDENTITY,
         }
     }
 
-    pub(crate) fn get_original_vertices(&self) -> &[Point2; N]
+    pub(crate) fn get_deformations(&self) -> &Matrix3
+    {
+        &self.deformations
+    }
+
+    pub(crate) fn get_original_vertices(&self) -> &[Point2f; N]
     {
         &self.vertices_orig
     }
 
-    pub(crate) fn get_current_verices(&self) -> &[Point2; N]
+    pub(crate) fn get_current_verices(&self) -> &[Point2f; N]
     {
         &self.vertices_curr
     }
@@ -46,28 +53,17 @@ impl<const N: usize> Shape for ConvexPolygon<N>
             let p1 = self.vertices_orig[index];
             let p2 = self.vertices_orig[(index + 1) % N];
 
-            let cross = (p1.x * p2.y) as f64 - (p2.x * p1.y) as f64;
+            let cross = p1.x * p2.y - p2.x * p1.y;
             area += cross;
         }
-        area * 0.5
+        // Rotations and translations preserve the area, deformations scale it by the determinant,
+        // which is negative for the reflections.
+        (area * 0.5 * self.deformations.determinant()).abs()
     }
 
-    fn bounding_box(&self) -> BoundingBox
+    fn bounding_box(&self) -> Aabb
     {
-        let vertices = self.get_current_verices();
-
-        let (max_x, min_x, max_y, min_y) = vertices.iter().fold(
-            (usize::MIN, usize::MAX, usize::MIN, usize::MAX),
-            |(max_x, min_x, max_y, min_y), vertex| {
-                (
-                    max_x.max(vertex.x),
-                    min_x.min(vertex.x),
-                    max_y.max(vertex.y),
-                    min_y.min(vertex.y),
-                )
-            },
-        );
-        BoundingBox::new(Point2::new(min_x, min_y), Point2::new(max_x, max_y))
+        Aabb::from_points(self.get_current_verices())
     }
 
     fn center(&self) -> Point2f
@@ -80,11 +76,11 @@ impl<const N: usize> Shape for ConvexPolygon<N>
         {
             let p1 = self.vertices_orig[index];
             let p2 = self.vertices_orig[(index + 1) % N];
-            let cross = (p1.x * p2.y) as f64 - (p2.x * p1.y) as f64;
+            let cross = p1.x * p2.y - p2.x * p1.y;
 
             area += cross;
-            center_x += (p1.x + p2.x) as f64 * cross;
-            center_y += (p1.y + p2.y) as f64 * cross;
+            center_x += (p1.x + p2.x) * cross;
+            center_y += (p1.y + p2.y) * cross;
         }
         area *= 0.5;
 
@@ -93,7 +89,7 @@ impl<const N: usize> Shape for ConvexPolygon<N>
         Point2f::new(center_x * factor, center_y * factor)
     }
 
-    fn contains(&self, point: Point2) -> bool
+    fn contains(&self, point: Point2f) -> bool
     {
         let mut first_sign = None;
 
@@ -102,16 +98,16 @@ impl<const N: usize> Shape for ConvexPolygon<N>
             let p1 = self.vertices_curr[index];
             let p2 = self.vertices_curr[(index + 1) % N];
 
-            let ax = p2.x as isize - p1.x as isize;
-            let ay = p2.y as isize - p1.y as isize;
-            let bx = point.x as isize - p1.x as isize;
-            let by = point.y as isize - p1.y as isize;
+            let ax = p2.x - p1.x;
+            let ay = p2.y - p1.y;
+            let bx = point.x - p1.x;
+            let by = point.y - p1.y;
 
             let cross = ax * by - ay * bx;
 
-            if cross != 0
+            if cross != 0.0
             {
-                let is_positive = cross > 0;
+                let is_positive = cross > 0.0;
 
                 if let Some(sign) = first_sign
                 {
@@ -129,17 +125,19 @@ impl<const N: usize> Shape for ConvexPolygon<N>
         true
     }
 
-    fn points_filled(&self) -> impl Iterator<Item = Point2>
+    fn points_filled(&self) -> impl Iterator<Item = Vec2>
     {
-        self.bounding_box().filter(|point| self.contains(*point))
+        self.bounding_box()
+            .cells()
+            .filter(|point| self.contains(point.to_point2f()))
     }
 
-    fn points_outline(&self) -> impl Iterator<Item = Point2>
+    fn points_outline(&self) -> impl Iterator<Item = Vec2>
     {
         (0..N).flat_map(|index| {
             Bresenham::new(
-                self.vertices_curr[index],
-                self.vertices_curr[(index + 1) % N],
+                self.vertices_curr[index].to_nearest_vec2(),
+                self.vertices_curr[(index + 1) % N].to_nearest_vec2(),
             )
         })
     }
@@ -154,7 +152,17 @@ impl<const N: usize> LazyShape for ConvexPolygon<N>
 {
     fn get_center(&self) -> Point2f
     {
-        self.center()
+        self.center().transform(&self.deformations)
+    }
+
+    fn get_deformations_mut(&mut self) -> &mut Matrix3
+    {
+        &mut self.deformations
+    }
+
+    fn get_vertices(&self) -> &[Point2f]
+    {
+        &self.vertices_curr
     }
 
     fn get_rotations_mut(&mut self) -> &mut Matrix3
@@ -169,7 +177,7 @@ impl<const N: usize> LazyShape for ConvexPolygon<N>
 
     fn perform_update(&mut self)
     {
-        let final_transform = self.translations * self.rotations;
+        let final_transform = self.translations * self.rotations * self.deformations;
 
         self.vertices_curr
             .iter_mut()
@@ -177,3 +185,81 @@ impl<const N: usize> LazyShape for ConvexPolygon<N>
             .for_each(|(curr, orig)| *curr = orig.transform(&final_transform));
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::linalg::Vec2f;
+
+    fn square(x: f64, y: f64, size: f64) -> ConvexPolygon<4>
+    {
+        ConvexPolygon::from_vertices([
+            Point2f::new(x, y),
+            Point2f::new(x + size, y),
+            Point2f::new(x + size, y + size),
+            Point2f::new(x, y + size),
+        ])
+    }
+
+    #[test]
+    fn rasterize_shape_with_negative_coordinates()
+    {
+        let polygon = square(-2.0, -2.0, 3.0);
+        let points = polygon.points_filled().collect::<Vec<_>>();
+
+        assert_eq!(points.len(), 16);
+        assert_eq!((points[0].x, points[0].y), (-2, -2));
+        assert_eq!((points[15].x, points[15].y), (1, 1));
+    }
+
+    #[test]
+    fn translate_shape_past_the_origin()
+    {
+        let mut polygon = square(1.0, 1.0, 2.0);
+
+        polygon
+            .transform()
+            .translate(Vec2f::new(-5.0, -5.0))
+            .finalize();
+
+        let bbox = polygon.bounding_box();
+
+        assert_eq!(bbox.min, Point2f::new(-4.0, -4.0));
+        assert_eq!(bbox.max, Point2f::new(-2.0, -2.0));
+    }
+
+    #[test]
+    fn reflected_shape_keeps_positive_area()
+    {
+        let mut polygon = square(1.0, 1.0, 3.0);
+
+        polygon
+            .transform()
+            .scale(2.0)
+            .reflect(Vec2f::new(0.0, 1.0))
+            .finalize();
+
+        assert_eq!(polygon.area(), 36.0);
+    }
+
+    #[test]
+    fn repeated_rotations_do_not_drift()
+    {
+        let mut polygon = square(10.0, 10.0, 4.0);
+
+        for _ in 0..360
+        {
+            polygon.transform().rotate(1.0).finalize();
+        }
+
+        for (curr, orig) in polygon
+            .vertices_curr
+            .iter()
+            .zip(polygon.vertices_orig.iter())
+        {
+            assert!((curr.x - orig.x).abs() < 1e-9);
+            assert!((curr.y - orig.y).abs() < 1e-9);
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::linalg::shapes::{BoundingBox, ConvexPolygon, LazyShape, LazyTransformer, Shape};
-use crate::linalg::{Point2, Point2f, Vec2};
+use crate::linalg::shapes::{Aabb, ConvexPolygon, LazyShape, LazyTransformer, Shape};
+use crate::linalg::{Matrix3, Point2f, Vec2, Vec2f};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Rectangle
@@ -9,41 +9,40 @@ pub struct Rectangle
 
 impl Rectangle
 {
-    pub fn from_corners(top_left: Point2, bottom_right: Point2) -> Self
+    pub fn from_corners(top_left: Point2f, bottom_right: Point2f) -> Self
     {
-        let top_right = top_left + Vec2::from_signed(bottom_right.x - top_left.x, 0);
-        let bottom_left = top_left + Vec2::from_signed(0, bottom_right.y - top_left.y);
+        let top_right = Point2f::new(bottom_right.x, top_left.y);
+        let bottom_left = Point2f::new(top_left.x, bottom_right.y);
         Self::from_vertices([top_left, top_right, bottom_right, bottom_left])
     }
 
-    pub fn from_corner_and_size(top_left: Point2, size: Vec2) -> Self
+    pub fn from_corner_and_size(top_left: Point2f, size: Vec2f) -> Self
     {
-        let bottom_right = top_left + size;
-        Self::from_corners(top_left, bottom_right)
+        Self::from_corners(top_left, top_left + size)
     }
 
-    pub fn from_vertices(vertices: [Point2; 4]) -> Self
+    pub fn from_vertices(vertices: [Point2f; 4]) -> Self
     {
         Self {
             polygon: ConvexPolygon::from_vertices(vertices),
         }
     }
 
-    pub fn width(&self) -> usize
+    pub fn width(&self) -> f64
     {
         let [tl, _, br, _] = self.polygon.get_original_vertices();
         br.x - tl.x
     }
 
-    pub fn height(&self) -> usize
+    pub fn height(&self) -> f64
     {
         let [tl, _, br, _] = self.polygon.get_original_vertices();
         br.y - tl.y
     }
 
-    pub fn size(&self) -> Vec2
+    pub fn size(&self) -> Vec2f
     {
-        Vec2::from_signed(self.width(), self.height())
+        Vec2f::new(self.width(), self.height())
     }
 }
 
@@ -51,35 +50,32 @@ impl Shape for Rectangle
 {
     fn area(&self) -> f64
     {
-        (self.width() * self.height()) as f64
+        let scale = self.polygon.get_deformations().determinant().abs();
+        self.width() * self.height() * scale
     }
 
-    fn bounding_box(&self) -> BoundingBox
+    fn bounding_box(&self) -> Aabb
     {
         self.polygon.bounding_box()
     }
 
     fn center(&self) -> Point2f
     {
-        let [tl, _, _, br] = self.polygon.get_original_vertices();
-
-        let center_x = (tl.x + br.x) as f64 / 2.0;
-        let center_y = (tl.y + br.y) as f64 / 2.0;
-
-        Point2f::new(center_x, center_y)
+        let [tl, _, br, _] = self.polygon.get_original_vertices();
+        tl.midpoint(*br)
     }
 
-    fn contains(&self, point: Point2) -> bool
+    fn contains(&self, point: Point2f) -> bool
     {
         self.polygon.contains(point)
     }
 
-    fn points_filled(&self) -> impl Iterator<Item = Point2>
+    fn points_filled(&self) -> impl Iterator<Item = Vec2>
     {
         self.polygon.points_filled()
     }
 
-    fn points_outline(&self) -> impl Iterator<Item = Point2>
+    fn points_outline(&self) -> impl Iterator<Item = Vec2>
     {
         self.polygon.points_outline()
     }
@@ -94,15 +90,25 @@ impl LazyShape for Rectangle
 {
     fn get_center(&self) -> Point2f
     {
-        self.center()
+        self.center().transform(self.polygon.get_deformations())
+    }
+
+    fn get_deformations_mut(&mut self) -> &mut Matrix3
+    {
+        self.polygon.get_deformations_mut()
+    }
+
+    fn get_vertices(&self) -> &[Point2f]
+    {
+        self.polygon.get_vertices()
     }
 
-    fn get_rotations_mut(&mut self) -> &mut crate::linalg::Matrix3
+    fn get_rotations_mut(&mut self) -> &mut Matrix3
     {
         self.polygon.get_rotations_mut()
     }
 
-    fn get_translations_mut(&mut self) -> &mut crate::linalg::Matrix3
+    fn get_translations_mut(&mut self) -> &mut Matrix3
     {
         self.polygon.get_translations_mut()
     }
@@ -1,9 +1,12 @@
-use crate::linalg::shapes::{BoundingBox, LazyTransformer};
-use crate::linalg::{Matrix3, Point2, Point2f};
+use crate::linalg::shapes::collision::{self, Contact, Ray, RayHit};
+use crate::linalg::shapes::{Aabb, LazyTransformer, Obb};
+use crate::linalg::{Matrix3, Point2f, Vec2};
 
 pub trait LazyShape
 {
     fn get_center(&self) -> Point2f;
+    fn get_vertices(&self) -> &[Point2f];
+    fn get_deformations_mut(&mut self) -> &mut Matrix3;
     fn get_rotations_mut(&mut self) -> &mut Matrix3;
     fn get_translations_mut(&mut self) -> &mut Matrix3;
     fn perform_update(&mut self);
@@ -14,10 +17,47 @@ where
     Self: LazyShape + Sized,
 {
     fn area(&self) -> f64;
-    fn bounding_box(&self) -> BoundingBox;
+    fn bounding_box(&self) -> Aabb;
     fn center(&self) -> Point2f;
-    fn contains(&self, point: Point2) -> bool;
-    fn points_filled(&self) -> impl Iterator<Item = Point2>;
-    fn points_outline(&self) -> impl Iterator<Item = Point2>;
+    fn contains(&self, point: Point2f) -> bool;
+    /// Rasterizes the whole shape into signed cell coordinates, which are not clipped in any way.
+    fn points_filled(&self) -> impl Iterator<Item = Vec2>;
+    /// Rasterizes the part of the shape inside of the `area`, the cells outside of it are not
+    /// visited at all.
+    fn points_filled_within(&self, area: &Aabb) -> impl Iterator<Item = Vec2>
+    {
+        self.bounding_box()
+            .intersection(area)
+            .into_iter()
+            .flat_map(|visible| visible.cells())
+            .filter(|point| self.contains(point.to_point2f()))
+    }
+    /// Rasterizes the edges of the shape into signed cell coordinates, which are not clipped in any
+    /// way.
+    fn points_outline(&self) -> impl Iterator<Item = Vec2>;
     fn transform(&mut self) -> LazyTransformer<'_, Self>;
+
+    fn oriented_bounding_box(&self) -> Obb
+    {
+        Obb::enclosing(self.get_vertices())
+    }
+
+    /// Checks the shapes against each other using the separating axis theorem. The contact
+    /// normal points from `self` towards the `other` shape.
+    fn collide<S: Shape>(&self, other: &S) -> Option<Contact>
+    {
+        collision::collide(self.get_vertices(), other.get_vertices())
+    }
+
+    fn intersects<S: Shape>(&self, other: &S) -> bool
+    {
+        self.collide(other).is_some()
+    }
+
+    /// Finds the closest edge hit by the ray. A ray starting inside of the shape hits the edge it
+    /// leaves through.
+    fn raycast(&self, ray: &Ray) -> Option<RayHit>
+    {
+        collision::raycast(self.get_vertices(), ray)
+    }
 }
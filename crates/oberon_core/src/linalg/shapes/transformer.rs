@@ -31,8 +31,118 @@ impl<'a, S: LazyShape> LazyTransformer<'a, S>
         self
     }
 
+    pub fn scale(self, by: f64) -> Self
+    {
+        self.scale_xy(Vec2f::new(by, by))
+    }
+
+    pub fn scale_xy(self, by: Vec2f) -> Self
+    {
+        let center = self.shape.get_center();
+        self.scale_around(center, by)
+    }
+
+    /// Scales the shape around the `around` point, given in the shape coordinates before it is
+    /// rotated and translated.
+    pub fn scale_around(self, around: Point2f, by: Vec2f) -> Self
+    {
+        self.apply(Matrix3::scaling_around(around, by))
+    }
+
+    pub fn shear(self, by: Vec2f) -> Self
+    {
+        let center = self.shape.get_center();
+        self.shear_around(center, by)
+    }
+
+    /// Shears the shape around the `around` point, given in the shape coordinates before it is
+    /// rotated and translated.
+    pub fn shear_around(self, around: Point2f, by: Vec2f) -> Self
+    {
+        self.apply(Matrix3::shearing_around(around, by))
+    }
+
+    pub fn reflect(self, axis: Vec2f) -> Self
+    {
+        let center = self.shape.get_center();
+        self.reflect_around(center, axis)
+    }
+
+    /// Reflects the shape over the `axis` going through the `around` point, given in the shape
+    /// coordinates before it is rotated and translated.
+    pub fn reflect_around(self, around: Point2f, axis: Vec2f) -> Self
+    {
+        self.apply(Matrix3::reflection_around(around, axis))
+    }
+
+    /// Applies any transformation to the shape before it is rotated and translated. Transformations
+    /// applied this way are composed in the order of calls.
+    pub fn apply(self, transform: Matrix3) -> Self
+    {
+        let deformations = self.shape.get_deformations_mut();
+        *deformations = transform * *deformations;
+        self
+    }
+
     pub fn finalize(&mut self)
     {
         self.shape.perform_update();
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use crate::linalg::shapes::{Rectangle, Shape};
+    use crate::linalg::{Point2f, Vec2f};
+
+    #[test]
+    fn scale_keeps_the_center_in_place()
+    {
+        let mut rectangle =
+            Rectangle::from_corner_and_size(Point2f::new(10.0, 10.0), Vec2f::new(4.0, 4.0));
+
+        rectangle.transform().scale(2.0).finalize();
+
+        let bbox = rectangle.bounding_box();
+
+        assert_eq!(bbox.min, Point2f::new(8.0, 8.0));
+        assert_eq!(bbox.max, Point2f::new(16.0, 16.0));
+        assert_eq!(rectangle.area(), 64.0);
+    }
+
+    #[test]
+    fn deformations_are_applied_before_translations()
+    {
+        let mut rectangle = Rectangle::from_corner_and_size(Point2f::ZERO, Vec2f::new(2.0, 2.0));
+
+        rectangle
+            .transform()
+            .translate(Vec2f::new(10.0, 0.0))
+            .scale_xy(Vec2f::new(2.0, 1.0))
+            .finalize();
+
+        let bbox = rectangle.bounding_box();
+
+        assert_eq!(bbox.min, Point2f::new(9.0, 0.0));
+        assert_eq!(bbox.max, Point2f::new(13.0, 2.0));
+    }
+
+    #[test]
+    fn reflect_flips_the_shape_around_its_center()
+    {
+        let mut rectangle =
+            Rectangle::from_corner_and_size(Point2f::new(2.0, 2.0), Vec2f::new(4.0, 2.0));
+
+        rectangle
+            .transform()
+            .reflect(Vec2f::new(1.0, 1.0))
+            .finalize();
+
+        let bbox = rectangle.bounding_box();
+
+        assert_eq!(bbox.min, Point2f::new(3.0, 1.0));
+        assert_eq!(bbox.max, Point2f::new(5.0, 5.0));
+        assert_eq!(rectangle.area(), 8.0);
+    }
+}
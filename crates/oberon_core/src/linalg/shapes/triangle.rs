@@ -1,5 +1,5 @@
-use crate::linalg::shapes::{BoundingBox, ConvexPolygon, LazyShape, LazyTransformer, Shape};
-use crate::linalg::{Matrix3, Point2, Point2f};
+use crate::linalg::shapes::{Aabb, ConvexPolygon, LazyShape, LazyTransformer, Shape};
+use crate::linalg::{Matrix3, Point2f, Vec2};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Triangle
@@ -9,7 +9,7 @@ pub struct Triangle
 
 impl Triangle
 {
-    pub fn from_vertices(vertices: [Point2; 3]) -> Self
+    pub fn from_vertices(vertices: [Point2f; 3]) -> Self
     {
         Self {
             polygon: ConvexPolygon::from_vertices(vertices),
@@ -24,7 +24,7 @@ impl Shape for Triangle
         self.polygon.area()
     }
 
-    fn bounding_box(&self) -> BoundingBox
+    fn bounding_box(&self) -> Aabb
     {
         self.polygon.bounding_box()
     }
@@ -33,23 +33,23 @@ impl Shape for Triangle
     {
         let [f, s, t] = self.polygon.get_original_vertices();
 
-        let center_x = (f.x + s.x + t.x) as f64 / 3.0;
-        let center_y = (f.y + s.y + t.y) as f64 / 3.0;
+        let center_x = (f.x + s.x + t.x) / 3.0;
+        let center_y = (f.y + s.y + t.y) / 3.0;
 
         Point2f::new(center_x, center_y)
     }
 
-    fn contains(&self, point: Point2) -> bool
+    fn contains(&self, point: Point2f) -> bool
     {
         self.polygon.contains(point)
     }
 
-    fn points_filled(&self) -> impl Iterator<Item = Point2>
+    fn points_filled(&self) -> impl Iterator<Item = Vec2>
     {
         self.polygon.points_filled()
     }
 
-    fn points_outline(&self) -> impl Iterator<Item = Point2>
+    fn points_outline(&self) -> impl Iterator<Item = Vec2>
     {
         self.polygon.points_outline()
     }
@@ -64,7 +64,17 @@ impl LazyShape for Triangle
 {
     fn get_center(&self) -> Point2f
     {
-        self.center()
+        self.center().transform(self.polygon.get_deformations())
+    }
+
+    fn get_deformations_mut(&mut self) -> &mut Matrix3
+    {
+        self.polygon.get_deformations_mut()
+    }
+
+    fn get_vertices(&self) -> &[Point2f]
+    {
+        self.polygon.get_vertices()
     }
 
     fn get_rotations_mut(&mut self) -> &mut Matrix3
@@ -0,0 +1,183 @@
+use crate::linalg::{Matrix3, Point2f, Vec2f};
+
+/// Translation, rotation (in degrees) and scale, applied to points in the reverse order. The
+/// builder methods apply the transformation after the current one, so their order matters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D
+{
+    pub translation: Vec2f,
+    pub rotation: f64,
+    pub scale: Vec2f,
+}
+
+impl Transform2D
+{
+    pub const IDENTITY: Self = Self::new(Vec2f::ZEROES, 0.0, Vec2f::ONES);
+
+    pub const fn new(translation: Vec2f, rotation: f64, scale: Vec2f) -> Self
+    {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Rotates around the origin, together with the current translation.
+    pub fn rotate(mut self, angle: f64) -> Self
+    {
+        self.translation = Matrix3::rotation(angle) * self.translation;
+        self.rotation += angle;
+        self
+    }
+
+    /// Scales from the origin, together with the current translation. The scale stays along the
+    /// own axes, so a non-uniform scale after a rotation does not shear like the matrices would.
+    pub fn scale(mut self, by: Vec2f) -> Self
+    {
+        self.translation = Vec2f::new(self.translation.x * by.x, self.translation.y * by.y);
+        self.scale = Vec2f::new(self.scale.x * by.x, self.scale.y * by.y);
+        self
+    }
+
+    pub fn translate(mut self, by: Vec2f) -> Self
+    {
+        self.translation += by;
+        self
+    }
+
+    /// Transformation of the `child` expressed in the space this transformation is in. It is kept
+    /// as a matrix, since a non-uniform scale of a rotated child shears it.
+    pub fn combine(&self, child: &Transform2D) -> Matrix3
+    {
+        self.to_matrix() * child.to_matrix()
+    }
+
+    pub fn inverse_matrix(&self) -> Option<Matrix3>
+    {
+        self.to_matrix().inverse()
+    }
+
+    /// Maps the point back, e.g. from the screen to the world space. `None` when any of the
+    /// scale components is zero.
+    pub fn inverse_transform_point(&self, point: Point2f) -> Option<Point2f>
+    {
+        self.inverse_matrix().map(|inverse| inverse * point)
+    }
+
+    pub fn to_matrix(&self) -> Matrix3
+    {
+        Matrix3::translation(self.translation)
+            * Matrix3::rotation(self.rotation)
+            * Matrix3::scaling(self.scale)
+    }
+
+    pub fn transform_point(&self, point: Point2f) -> Point2f
+    {
+        self.to_matrix() * point
+    }
+}
+
+impl Default for Transform2D
+{
+    fn default() -> Self
+    {
+        Self::IDENTITY
+    }
+}
+
+impl From<Transform2D> for Matrix3
+{
+    fn from(value: Transform2D) -> Self
+    {
+        value.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_close(a: Point2f, b: Point2f)
+    {
+        assert!(a.distance(b) < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn scale_then_rotate_then_translate()
+    {
+        let transform = Transform2D::IDENTITY
+            .scale(Vec2f::new(2.0, 2.0))
+            .rotate(90.0)
+            .translate(Vec2f::new(10.0, 0.0));
+
+        let reordered = Transform2D::IDENTITY
+            .translate(Vec2f::new(10.0, 0.0))
+            .rotate(90.0)
+            .scale(Vec2f::new(2.0, 2.0));
+
+        assert_close(
+            transform.transform_point(Point2f::new(1.0, 0.0)),
+            Point2f::new(10.0, 2.0),
+        );
+        assert_close(
+            reordered.transform_point(Point2f::new(1.0, 0.0)),
+            Point2f::new(0.0, 22.0),
+        );
+    }
+
+    #[test]
+    fn inverse_transform_point_picks_the_original()
+    {
+        let transform = Transform2D::new(Vec2f::new(-3.0, 8.0), 45.0, Vec2f::new(0.5, 4.0));
+        let world = Point2f::new(2.0, -7.0);
+        let screen = transform.transform_point(world);
+
+        assert_close(transform.inverse_transform_point(screen).unwrap(), world);
+    }
+
+    #[test]
+    fn zero_scale_cannot_be_inverted()
+    {
+        let transform = Transform2D::IDENTITY.scale(Vec2f::new(0.0, 1.0));
+
+        assert!(transform.inverse_transform_point(Point2f::ZERO).is_none());
+    }
+
+    #[test]
+    fn combine_matches_matrix_multiplication()
+    {
+        let parent = Transform2D::new(Vec2f::new(5.0, 5.0), 90.0, Vec2f::ONES);
+        let child = Transform2D::new(Vec2f::new(1.0, 0.0), 0.0, Vec2f::new(2.0, 2.0));
+
+        let combined = parent.combine(&child);
+
+        assert_close(combined * Point2f::ZERO, Point2f::new(5.0, 6.0));
+        assert_close(combined * Point2f::new(1.0, 0.0), Point2f::new(5.0, 8.0));
+    }
+
+    #[test]
+    fn combine_keeps_the_shear()
+    {
+        let parent = Transform2D::new(Vec2f::ZEROES, 0.0, Vec2f::new(2.0, 1.0));
+        let child = Transform2D::new(Vec2f::ZEROES, 45.0, Vec2f::ONES);
+
+        let combined = parent.combine(&child);
+        let diagonal = Point2f::new(1.0, 1.0);
+        let half = 2.0_f64.sqrt() / 2.0;
+
+        assert_close(
+            combined * Point2f::new(1.0, 0.0),
+            Point2f::new(2.0 * half, half),
+        );
+        assert_close(combined * diagonal, Point2f::new(0.0, 2.0 * half));
+        assert!(
+            combined
+                .decompose()
+                .transform_point(diagonal)
+                .distance(combined * diagonal)
+                > 0.1
+        );
+    }
+}
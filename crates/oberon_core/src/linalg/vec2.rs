@@ -1,8 +1,8 @@
-use std::ops::{Add, AddAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::linalg::Vec2f;
+use crate::linalg::{Point2f, Vec2f};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Vec2
 {
     pub x: isize,
@@ -30,6 +30,36 @@ impl Vec2
         }
     }
 
+    pub const fn cross(&self, other: Vec2) -> isize
+    {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub const fn dot(&self, other: Vec2) -> isize
+    {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(&self) -> f64
+    {
+        (self.length_squared() as f64).sqrt()
+    }
+
+    pub const fn length_squared(&self) -> isize
+    {
+        self.dot(*self)
+    }
+
+    pub const fn perpendicular(&self) -> Self
+    {
+        Self::new(-self.y, self.x)
+    }
+
+    pub const fn to_point2f(&self) -> Point2f
+    {
+        Point2f::new(self.x as f64, self.y as f64)
+    }
+
     pub const fn to_vec2f(&self) -> Vec2f
     {
         Vec2f::new(self.x as f64, self.y as f64)
@@ -73,3 +103,123 @@ impl AddAssign<isize> for Vec2
         self.y += rhs;
     }
 }
+
+impl Sub<Vec2> for Vec2
+{
+    type Output = Vec2;
+
+    fn sub(self, rhs: Vec2) -> Self::Output
+    {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vec2> for Vec2
+{
+    fn sub_assign(&mut self, rhs: Vec2)
+    {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Sub<isize> for Vec2
+{
+    type Output = Vec2;
+
+    fn sub(self, rhs: isize) -> Self::Output
+    {
+        Vec2::new(self.x - rhs, self.y - rhs)
+    }
+}
+
+impl SubAssign<isize> for Vec2
+{
+    fn sub_assign(&mut self, rhs: isize)
+    {
+        self.x -= rhs;
+        self.y -= rhs;
+    }
+}
+
+impl Mul<isize> for Vec2
+{
+    type Output = Vec2;
+
+    fn mul(self, rhs: isize) -> Self::Output
+    {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl MulAssign<isize> for Vec2
+{
+    fn mul_assign(&mut self, rhs: isize)
+    {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl Div<isize> for Vec2
+{
+    type Output = Vec2;
+
+    fn div(self, rhs: isize) -> Self::Output
+    {
+        Vec2::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl DivAssign<isize> for Vec2
+{
+    fn div_assign(&mut self, rhs: isize)
+    {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl Neg for Vec2
+{
+    type Output = Vec2;
+
+    fn neg(self) -> Self::Output
+    {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn arithmetic_operators()
+    {
+        let mut vec = Vec2::new(4, -2) - Vec2::new(1, 1);
+
+        assert_eq!(vec, Vec2::new(3, -3));
+        assert_eq!(vec * 2, Vec2::new(6, -6));
+        assert_eq!(vec / 3, Vec2::new(1, -1));
+        assert_eq!(-vec, Vec2::new(-3, 3));
+
+        vec -= 1;
+        vec *= -1;
+
+        assert_eq!(vec, Vec2::new(-2, 4));
+    }
+
+    #[test]
+    fn products_and_length()
+    {
+        let a = Vec2::new(3, 4);
+        let b = Vec2::new(-4, 3);
+
+        assert_eq!(a.dot(b), 0);
+        assert_eq!(a.cross(b), 25);
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a.perpendicular(), b);
+    }
+}
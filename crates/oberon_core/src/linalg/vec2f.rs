@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, Debug)]
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::linalg::{Point2f, Vec2};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec2f
 {
     pub x: f64,
@@ -7,13 +11,253 @@ pub struct Vec2f
 
 impl Vec2f
 {
+    pub const ZEROES: Self = Self::new(0.0, 0.0);
+    pub const ONES: Self = Self::new(1.0, 1.0);
+    pub const RIGHT: Self = Self::new(1.0, 0.0);
+    pub const LEFT: Self = Self::new(-1.0, 0.0);
+    pub const UP: Self = Self::new(0.0, 1.0);
+
     pub const fn new(x: f64, y: f64) -> Self
     {
         Self { x, y }
     }
 
+    /// Unit vector pointing at the angle given in degrees.
+    pub fn from_angle(angle: f64) -> Self
+    {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::new(cos, sin)
+    }
+
     pub const fn opposite(&self) -> Self
     {
         Self::new(-self.x, -self.y)
     }
+
+    /// Angle between the vector and the x axis in degrees.
+    pub fn angle(&self) -> f64
+    {
+        self.y.atan2(self.x).to_degrees()
+    }
+
+    /// Signed angle in degrees needed to rotate this vector onto the `other` one.
+    pub fn angle_to(&self, other: Vec2f) -> f64
+    {
+        self.cross(other).atan2(self.dot(other)).to_degrees()
+    }
+
+    pub fn cross(&self, other: Vec2f) -> f64
+    {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn dot(&self, other: Vec2f) -> f64
+    {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(&self) -> f64
+    {
+        self.x.hypot(self.y)
+    }
+
+    pub fn length_squared(&self) -> f64
+    {
+        self.dot(*self)
+    }
+
+    pub fn lerp(&self, other: Vec2f, t: f64) -> Self
+    {
+        *self + (other - *self) * t
+    }
+
+    /// Vector of the same direction and the length of one, zero vector stays as it is.
+    pub fn normalize(&self) -> Self
+    {
+        let length = self.length();
+
+        if length == 0.0
+        {
+            return *self;
+        }
+        *self / length
+    }
+
+    pub fn perpendicular(&self) -> Self
+    {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the vector by the angle given in degrees, the same way `Matrix3` rotations do.
+    pub fn rotate(&self, angle: f64) -> Self
+    {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    pub const fn to_point2f(&self) -> Point2f
+    {
+        Point2f::new(self.x, self.y)
+    }
+
+    /// Rounds the vector to the closest integer one.
+    pub fn to_nearest_vec2(&self) -> Vec2
+    {
+        Vec2::new(self.x.round() as isize, self.y.round() as isize)
+    }
+}
+
+impl From<Vec2> for Vec2f
+{
+    fn from(value: Vec2) -> Self
+    {
+        value.to_vec2f()
+    }
+}
+
+impl Add<Vec2f> for Vec2f
+{
+    type Output = Vec2f;
+
+    fn add(self, rhs: Vec2f) -> Self::Output
+    {
+        Vec2f::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vec2f> for Vec2f
+{
+    fn add_assign(&mut self, rhs: Vec2f)
+    {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub<Vec2f> for Vec2f
+{
+    type Output = Vec2f;
+
+    fn sub(self, rhs: Vec2f) -> Self::Output
+    {
+        Vec2f::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vec2f> for Vec2f
+{
+    fn sub_assign(&mut self, rhs: Vec2f)
+    {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Mul<f64> for Vec2f
+{
+    type Output = Vec2f;
+
+    fn mul(self, rhs: f64) -> Self::Output
+    {
+        Vec2f::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl MulAssign<f64> for Vec2f
+{
+    fn mul_assign(&mut self, rhs: f64)
+    {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl Div<f64> for Vec2f
+{
+    type Output = Vec2f;
+
+    fn div(self, rhs: f64) -> Self::Output
+    {
+        Vec2f::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl DivAssign<f64> for Vec2f
+{
+    fn div_assign(&mut self, rhs: f64)
+    {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl Neg for Vec2f
+{
+    type Output = Vec2f;
+
+    fn neg(self) -> Self::Output
+    {
+        self.opposite()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn assert_close(a: Vec2f, b: Vec2f)
+    {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn arithmetic_operators()
+    {
+        let mut vec = Vec2f::new(1.0, 2.0) + Vec2f::new(2.0, 2.0);
+
+        assert_eq!(vec * 2.0, Vec2f::new(6.0, 8.0));
+        assert_eq!(vec / 2.0, Vec2f::new(1.5, 2.0));
+        assert_eq!(-vec, Vec2f::new(-3.0, -4.0));
+
+        vec -= Vec2f::ONES;
+        vec /= 2.0;
+
+        assert_eq!(vec, Vec2f::new(1.0, 1.5));
+    }
+
+    #[test]
+    fn length_and_normalize()
+    {
+        let vec = Vec2f::new(3.0, 4.0);
+
+        assert_eq!(vec.length(), 5.0);
+        assert_eq!(vec.length_squared(), 25.0);
+        assert_close(vec.normalize(), Vec2f::new(0.6, 0.8));
+        assert_eq!(Vec2f::ZEROES.normalize(), Vec2f::ZEROES);
+    }
+
+    #[test]
+    fn angles_and_rotations()
+    {
+        let vec = Vec2f::new(0.0, 2.0);
+
+        assert_eq!(vec.angle(), 90.0);
+        assert_close(vec.rotate(90.0), Vec2f::new(-2.0, 0.0));
+        assert_close(Vec2f::from_angle(180.0), Vec2f::LEFT);
+        assert!((Vec2f::RIGHT.angle_to(vec) - 90.0).abs() < 1e-9);
+        assert!((vec.angle_to(Vec2f::RIGHT) + 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn products_and_lerp()
+    {
+        let a = Vec2f::new(1.0, 0.0);
+        let b = Vec2f::new(3.0, 4.0);
+
+        assert_eq!(a.dot(b), 3.0);
+        assert_eq!(a.cross(b), 4.0);
+        assert_eq!(a.lerp(b, 0.5), Vec2f::new(2.0, 2.0));
+        assert_eq!(Vec2f::from(Vec2::new(2, -1)), Vec2f::new(2.0, -1.0));
+    }
 }
@@ -6,14 +6,35 @@ use crate::style::Rgb;
 #[derive(Debug)]
 pub struct Renderer<W: Write>
 {
-    buffer: W,
+    buffer: Counted<W>,
+}
+
+/// Writer counting the bytes passed to the wrapped one.
+#[derive(Debug)]
+struct Counted<W: Write>
+{
+    inner: W,
+    written: usize,
 }
 
 impl<W: Write> Renderer<W>
 {
     pub fn new(buffer: W) -> Self
     {
-        Self { buffer }
+        Self {
+            buffer: Counted {
+                inner: buffer,
+                written: 0,
+            },
+        }
+    }
+
+    /// Number of bytes written since the renderer was created, including the ones still
+    /// buffered.
+    #[inline]
+    pub fn bytes_written(&self) -> usize
+    {
+        self.buffer.written
     }
 
     pub fn change_bg(&mut self, color: &Rgb) -> IoResult<()>
@@ -74,3 +95,35 @@ impl<W: Write> Renderer<W>
         self.buffer.write_all(c.encode_utf8(&mut [0; 2]).as_bytes())
     }
 }
+
+impl<W: Write> Write for Counted<W>
+{
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize>
+    {
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> IoResult<()>
+    {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn count_written_bytes()
+    {
+        let mut renderer = Renderer::new(Vec::new());
+
+        renderer.move_cursor(Point2::new(1, 2)).unwrap();
+        renderer.write('ą').unwrap();
+
+        assert_eq!(renderer.bytes_written(), "\x1B[3;2H".len() + 'ą'.len_utf8());
+    }
+}
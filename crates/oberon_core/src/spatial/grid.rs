@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::linalg::shapes::Aabb;
+use crate::spatial::SpatialIndex;
+
+type CellRange = ((isize, isize), (isize, isize));
+
+/// Spatial index bucketing the keys into square cells of the same size. Works best when the
+/// indexed bounds are similar in size to the cells.
+#[derive(Debug)]
+pub struct UniformGrid<K>
+{
+    cell_size: f64,
+    cells: HashMap<(isize, isize), Vec<K>>,
+    entries: HashMap<K, Aabb>,
+}
+
+impl<K> UniformGrid<K>
+where
+    K: Copy + Eq + Hash,
+{
+    /// Panics if the `cell_size` is not a positive finite number.
+    pub fn new(cell_size: f64) -> Self
+    {
+        assert!(
+            cell_size > 0.0 && cell_size.is_finite(),
+            "Cell size has to be positive and finite, got `{cell_size}`."
+        );
+
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    fn cell_range(&self, bounds: &Aabb) -> CellRange
+    {
+        let cell = |value: f64| (value / self.cell_size).floor() as isize;
+
+        (
+            (cell(bounds.min.x), cell(bounds.min.y)),
+            (cell(bounds.max.x), cell(bounds.max.y)),
+        )
+    }
+
+    fn for_each_cell(range: CellRange, mut f: impl FnMut((isize, isize)))
+    {
+        let ((min_x, min_y), (max_x, max_y)) = range;
+
+        for y in min_y..=max_y
+        {
+            for x in min_x..=max_x
+            {
+                f((x, y));
+            }
+        }
+    }
+}
+
+impl<K> SpatialIndex<K> for UniformGrid<K>
+where
+    K: Copy + Eq + Hash,
+{
+    fn get(&self, key: K) -> Option<Aabb>
+    {
+        self.entries.get(&key).copied()
+    }
+
+    fn insert(&mut self, key: K, bounds: Aabb)
+    {
+        if self.entries.contains_key(&key)
+        {
+            return self.update(key, bounds);
+        }
+
+        let range = self.cell_range(&bounds);
+
+        Self::for_each_cell(range, |cell| {
+            self.cells.entry(cell).or_default().push(key);
+        });
+        self.entries.insert(key, bounds);
+    }
+
+    fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
+
+    fn query_rect(&self, area: &Aabb) -> Vec<K>
+    {
+        let range = self.cell_range(area);
+        let ((min_x, min_y), (max_x, max_y)) = range;
+
+        let mut candidates = HashSet::new();
+        let covered_cells = (max_x.abs_diff(min_x).saturating_add(1))
+            .saturating_mul(max_y.abs_diff(min_y).saturating_add(1));
+
+        // Huge areas would visit mostly empty cells, checking the occupied ones is cheaper.
+        if covered_cells > self.cells.len()
+        {
+            self.cells
+                .iter()
+                .filter(|((x, y), _)| (min_x..=max_x).contains(x) && (min_y..=max_y).contains(y))
+                .for_each(|(_, keys)| candidates.extend(keys));
+        }
+        else
+        {
+            Self::for_each_cell(range, |cell| {
+                if let Some(keys) = self.cells.get(&cell)
+                {
+                    candidates.extend(keys);
+                }
+            });
+        }
+
+        candidates
+            .into_iter()
+            .filter(|key| self.entries[key].overlaps(area))
+            .collect()
+    }
+
+    fn remove(&mut self, key: K) -> Option<Aabb>
+    {
+        let bounds = self.entries.remove(&key)?;
+        let range = self.cell_range(&bounds);
+
+        Self::for_each_cell(range, |cell| {
+            if let Some(keys) = self.cells.get_mut(&cell)
+            {
+                keys.retain(|stored| *stored != key);
+
+                if keys.is_empty()
+                {
+                    self.cells.remove(&cell);
+                }
+            }
+        });
+        Some(bounds)
+    }
+
+    fn update(&mut self, key: K, bounds: Aabb)
+    {
+        let Some(current) = self.entries.get(&key).copied()
+        else
+        {
+            return self.insert(key, bounds);
+        };
+
+        // Small moves within the same cells do not touch the buckets at all.
+        if self.cell_range(&current) == self.cell_range(&bounds)
+        {
+            self.entries.insert(key, bounds);
+            return;
+        }
+        self.remove(key);
+        self.insert(key, bounds);
+    }
+}
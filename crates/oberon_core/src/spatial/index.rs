@@ -0,0 +1,71 @@
+use std::hash::Hash;
+
+use crate::linalg::shapes::Aabb;
+use crate::linalg::Point2f;
+
+pub trait SpatialIndex<K>
+where
+    K: Copy + Eq + Hash,
+{
+    fn get(&self, key: K) -> Option<Aabb>;
+    fn insert(&mut self, key: K, bounds: Aabb);
+    fn len(&self) -> usize;
+    fn query_rect(&self, area: &Aabb) -> Vec<K>;
+    fn remove(&mut self, key: K) -> Option<Aabb>;
+
+    /// Moves already indexed key to the new bounds, inserts it if it was not indexed.
+    fn update(&mut self, key: K, bounds: Aabb)
+    {
+        self.remove(key);
+        self.insert(key, bounds);
+    }
+
+    fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// Keys with bounds closer to the `center` than the `radius`.
+    fn query_radius(&self, center: Point2f, radius: f64) -> Vec<K>
+    {
+        let area = Aabb::new(center, center).expand(radius);
+
+        self.query_rect(&area)
+            .into_iter()
+            .filter(|key| {
+                self.get(*key)
+                    .is_some_and(|bounds| bounds.distance_to(center) <= radius)
+            })
+            .collect()
+    }
+
+    /// Up to `count` keys closest to the `point`, sorted from the closest one.
+    fn nearest(&self, point: Point2f, count: usize) -> Vec<K>
+    {
+        if count == 0 || self.is_empty()
+        {
+            return Vec::new();
+        }
+
+        let distance = |key: &K| {
+            self.get(*key)
+                .map_or(f64::MAX, |bounds| bounds.distance_to(point))
+        };
+        let mut radius = 1.0;
+
+        loop
+        {
+            let mut found = self.query_radius(point, radius);
+
+            // Everything closer than the k-th found key is inside of the radius already.
+            if found.len() >= count || found.len() == self.len()
+            {
+                found.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+                found.truncate(count);
+
+                return found;
+            }
+            radius *= 2.0;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+mod grid;
+pub use grid::UniformGrid;
+
+mod index;
+pub use index::SpatialIndex;
+
+mod quadtree;
+pub use quadtree::QuadTree;
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::linalg::shapes::Aabb;
+    use crate::linalg::Point2f;
+
+    fn cell(x: f64, y: f64) -> Aabb
+    {
+        Aabb::new(Point2f::new(x, y), Point2f::new(x + 1.0, y + 1.0))
+    }
+
+    fn indexes() -> [Box<dyn SpatialIndex<usize>>; 2]
+    {
+        let area = Aabb::new(Point2f::ZERO, Point2f::new(64.0, 64.0));
+
+        [
+            Box::new(UniformGrid::new(4.0)),
+            Box::new(QuadTree::new(area).capacity(2)),
+        ]
+    }
+
+    fn sorted(mut keys: Vec<usize>) -> Vec<usize>
+    {
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn query_rect_returns_overlapping_keys()
+    {
+        for mut index in indexes()
+        {
+            for id in 0..20
+            {
+                index.insert(id, cell(id as f64 * 3.0, 5.0));
+            }
+            let area = Aabb::new(Point2f::new(4.0, 0.0), Point2f::new(12.5, 10.0));
+
+            assert_eq!(sorted(index.query_rect(&area)), vec![1, 2, 3, 4]);
+            assert_eq!(index.len(), 20);
+        }
+    }
+
+    #[test]
+    fn query_radius_measures_distance_to_bounds()
+    {
+        for mut index in indexes()
+        {
+            index.insert(0, cell(10.0, 10.0));
+            index.insert(1, cell(13.0, 10.0));
+            index.insert(2, cell(10.0, 14.0));
+
+            let found = index.query_radius(Point2f::new(10.5, 10.5), 2.6);
+
+            assert_eq!(sorted(found), vec![0, 1]);
+        }
+    }
+
+    #[test]
+    fn nearest_returns_keys_sorted_by_distance()
+    {
+        for mut index in indexes()
+        {
+            index.insert(0, cell(50.0, 50.0));
+            index.insert(1, cell(2.0, 2.0));
+            index.insert(2, cell(20.0, 2.0));
+            index.insert(3, cell(6.0, 2.0));
+
+            assert_eq!(index.nearest(Point2f::ZERO, 3), vec![1, 3, 2]);
+            assert_eq!(index.nearest(Point2f::ZERO, 10).len(), 4);
+            assert!(index.nearest(Point2f::ZERO, 0).is_empty());
+        }
+    }
+
+    #[test]
+    fn update_moves_key_to_new_bounds()
+    {
+        for mut index in indexes()
+        {
+            for id in 0..10
+            {
+                index.insert(id, cell(id as f64, id as f64));
+            }
+            index.update(3, cell(40.0, 40.0));
+
+            let old_area = cell(3.0, 3.0).expand(-0.1);
+            let new_area = cell(40.0, 40.0);
+
+            assert!(!index.query_rect(&old_area).contains(&3));
+            assert_eq!(index.query_rect(&new_area), vec![3]);
+            assert_eq!(index.len(), 10);
+        }
+    }
+
+    #[test]
+    fn remove_forgets_the_key()
+    {
+        for mut index in indexes()
+        {
+            index.insert(7, cell(1.0, 1.0));
+            index.insert(8, cell(100.0, 100.0));
+
+            assert_eq!(index.remove(7), Some(cell(1.0, 1.0)));
+            assert_eq!(index.remove(7), None);
+            assert_eq!(index.remove(8), Some(cell(100.0, 100.0)));
+            assert!(index.query_rect(&cell(1.0, 1.0)).is_empty());
+            assert!(index.is_empty());
+        }
+    }
+
+    #[test]
+    fn grid_queries_huge_areas()
+    {
+        let mut grid = UniformGrid::new(4.0);
+        grid.insert(0, cell(1.0, 1.0));
+        grid.insert(1, cell(-100.0, 50.0));
+
+        let area = Aabb::new(Point2f::new(-1e300, -1e300), Point2f::new(1e300, 1e300));
+
+        assert_eq!(sorted(grid.query_rect(&area)), vec![0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn grid_rejects_empty_cells()
+    {
+        UniformGrid::<usize>::new(0.0);
+    }
+}
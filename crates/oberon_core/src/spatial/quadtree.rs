@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::linalg::shapes::Aabb;
+use crate::linalg::Point2f;
+use crate::spatial::SpatialIndex;
+
+#[derive(Debug)]
+struct Node<K>
+{
+    bounds: Aabb,
+    depth: usize,
+    items: Vec<(K, Aabb)>,
+    children: Option<[usize; 4]>,
+}
+
+impl<K> Node<K>
+{
+    const fn new(bounds: Aabb, depth: usize) -> Self
+    {
+        Self {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+}
+
+/// Spatial index recursively splitting its area into quadrants. Handles bounds of very different
+/// sizes well, everything not fitting into the area is kept in the root.
+#[derive(Debug)]
+pub struct QuadTree<K>
+{
+    capacity: usize,
+    max_depth: usize,
+    nodes: Vec<Node<K>>,
+    entries: HashMap<K, Aabb>,
+}
+
+impl<K> QuadTree<K>
+where
+    K: Copy + Eq + Hash,
+{
+    pub fn new(bounds: Aabb) -> Self
+    {
+        Self {
+            capacity: 8,
+            max_depth: 8,
+            nodes: vec![Node::new(bounds, 0)],
+            entries: HashMap::new(),
+        }
+    }
+
+    /// How many items a node holds before it gets split.
+    pub fn capacity(mut self, capacity: usize) -> Self
+    {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self
+    {
+        self.max_depth = depth;
+        self
+    }
+
+    // Child of the node which fully contains the bounds, if there is one.
+    fn child_containing(&self, index: usize, bounds: &Aabb) -> Option<usize>
+    {
+        self.nodes[index].children.and_then(|children| {
+            children
+                .into_iter()
+                .find(|child| self.nodes[*child].bounds.contains(bounds))
+        })
+    }
+
+    fn insert_into(&mut self, mut index: usize, key: K, bounds: Aabb)
+    {
+        while let Some(child) = self.child_containing(index, &bounds)
+        {
+            index = child;
+        }
+        self.nodes[index].items.push((key, bounds));
+
+        let node = &self.nodes[index];
+
+        if node.children.is_none()
+            && node.items.len() > self.capacity
+            && node.depth < self.max_depth
+        {
+            self.split(index);
+        }
+    }
+
+    fn split(&mut self, index: usize)
+    {
+        let Aabb { min, max } = self.nodes[index].bounds;
+        let depth = self.nodes[index].depth + 1;
+        let mid = Point2f::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0);
+
+        let quadrants = [
+            Aabb::new(min, mid),
+            Aabb::new(Point2f::new(mid.x, min.y), Point2f::new(max.x, mid.y)),
+            Aabb::new(Point2f::new(min.x, mid.y), Point2f::new(mid.x, max.y)),
+            Aabb::new(mid, max),
+        ];
+        let first = self.nodes.len();
+
+        self.nodes
+            .extend(quadrants.map(|quadrant| Node::new(quadrant, depth)));
+        self.nodes[index].children = Some([first, first + 1, first + 2, first + 3]);
+
+        let items = std::mem::take(&mut self.nodes[index].items);
+
+        for (key, bounds) in items
+        {
+            self.insert_into(index, key, bounds);
+        }
+    }
+}
+
+impl<K> SpatialIndex<K> for QuadTree<K>
+where
+    K: Copy + Eq + Hash,
+{
+    fn get(&self, key: K) -> Option<Aabb>
+    {
+        self.entries.get(&key).copied()
+    }
+
+    fn insert(&mut self, key: K, bounds: Aabb)
+    {
+        if self.entries.contains_key(&key)
+        {
+            return self.update(key, bounds);
+        }
+        self.entries.insert(key, bounds);
+        self.insert_into(0, key, bounds);
+    }
+
+    fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
+
+    fn query_rect(&self, area: &Aabb) -> Vec<K>
+    {
+        let mut found = Vec::new();
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop()
+        {
+            let node = &self.nodes[index];
+
+            found.extend(
+                node.items
+                    .iter()
+                    .filter(|(_, bounds)| bounds.overlaps(area))
+                    .map(|(key, _)| *key),
+            );
+
+            if let Some(children) = node.children
+            {
+                stack.extend(
+                    children
+                        .into_iter()
+                        .filter(|child| self.nodes[*child].bounds.overlaps(area)),
+                );
+            }
+        }
+        found
+    }
+
+    fn remove(&mut self, key: K) -> Option<Aabb>
+    {
+        let bounds = self.entries.remove(&key)?;
+        let mut index = 0;
+
+        // Inserting walks down through the children fully containing the bounds and keeps the key
+        // where it stops, splitting a node moves its keys down the same way. Removing walks the
+        // same path until it finds the key, the emptied nodes are not merged back.
+        loop
+        {
+            let items = &mut self.nodes[index].items;
+
+            if let Some(position) = items.iter().position(|(stored, _)| *stored == key)
+            {
+                items.swap_remove(position);
+                break;
+            }
+            match self.child_containing(index, &bounds)
+            {
+                Some(child) => index = child,
+                None => break,
+            }
+        }
+        Some(bounds)
+    }
+}
@@ -31,6 +31,12 @@ impl Block
         self.cell = new_cell;
     }
 
+    #[inline]
+    pub fn cell(&self) -> Cell
+    {
+        self.cell
+    }
+
     pub fn is_dirty(&self) -> bool
     {
         self.dirty
@@ -1,7 +1,6 @@
 use std::io::{Result as IoResult, Write};
 
 use crate::canvas::Canvas;
-use crate::linalg::shapes::{Rectangle, Shape};
 use crate::linalg::{Point2, Vec2};
 use crate::renderer::Renderer;
 use crate::terminal::block::Block;
@@ -10,7 +9,7 @@ use crate::terminal::cell::Cell;
 #[derive(Debug)]
 pub struct Terminal
 {
-    working_area: Rectangle,
+    size: Vec2,
     cursor_ratio: usize,
     blocks: Vec<Block>,
 }
@@ -19,11 +18,10 @@ impl Terminal
 {
     pub fn new(size: Vec2, cursor_ratio: usize) -> Self
     {
-        let working_area = Rectangle::from_corner_and_size(Point2::ZERO, size);
-        let blocks = vec![Block::new(Cell::EMPTY, cursor_ratio); working_area.area() as usize];
+        let blocks = vec![Block::new(Cell::EMPTY, cursor_ratio); (size.x * size.y) as usize];
 
         Self {
-            working_area,
+            size,
             cursor_ratio,
             blocks,
         }
@@ -31,13 +29,42 @@ impl Terminal
 
     pub fn at(&mut self, position: Point2) -> &mut Block
     {
-        let index = block_position_to_buffer_index(position, self.working_area.width());
+        let index = block_position_to_buffer_index(position, self.size.x as usize);
         &mut self.blocks[index]
     }
 
+    /// Cell at the position, `None` outside of the terminal.
+    pub fn cell(&self, position: Point2) -> Option<Cell>
+    {
+        let fits = position.x < self.size.x as usize && position.y < self.size.y as usize;
+
+        fits.then(|| {
+            let index = block_position_to_buffer_index(position, self.size.x as usize);
+            self.blocks[index].cell()
+        })
+    }
+
+    /// Replaces every cell with the result of `f` called with its position and the current cell.
+    /// Only the changed cells are redrawn.
+    pub fn map_cells(&mut self, mut f: impl FnMut(Point2, Cell) -> Cell)
+    {
+        let width = self.size.x as usize;
+
+        for (index, block) in self.blocks.iter_mut().enumerate()
+        {
+            let position = Point2::new(index % width, index / width);
+            let cell = f(position, block.cell());
+
+            if cell != block.cell()
+            {
+                block.change_cell(cell);
+            }
+        }
+    }
+
     pub fn area(&self) -> f64
     {
-        self.working_area.area()
+        (self.size.x * self.size.y) as f64
     }
 
     pub fn canvas(&mut self) -> Canvas<'_>
@@ -55,7 +82,16 @@ impl Terminal
 
     pub fn render_frame<W: Write>(&mut self, renderer: &mut Renderer<W>) -> IoResult<()>
     {
-        let width = self.working_area.width();
+        self.render_dirty(renderer)?;
+        renderer.flush()
+    }
+
+    /// Writes the blocks changed since the previous frame without flushing the renderer, returns
+    /// how many of them were written.
+    pub fn render_dirty<W: Write>(&mut self, renderer: &mut Renderer<W>) -> IoResult<usize>
+    {
+        let width = self.size.x as usize;
+        let mut dirty = 0;
 
         for (index, block) in self
             .blocks
@@ -65,14 +101,15 @@ impl Terminal
         {
             let position = block_index_to_screen_position(index, self.cursor_ratio, width);
             block.render_cells(position, renderer)?;
+            dirty += 1;
         }
         renderer.move_cursor(Point2::ZERO)?;
-        renderer.flush()
+        Ok(dirty)
     }
 
     pub fn size(&self) -> Vec2
     {
-        self.working_area.size()
+        self.size
     }
 }
 
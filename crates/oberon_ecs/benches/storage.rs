@@ -0,0 +1,90 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use oberon_ecs::{StorageType, With, World};
+
+const ENTITIES: usize = 100_000;
+const ITERATIONS: u32 = 50;
+
+struct Position(f64, f64);
+
+struct Velocity(f64, f64);
+
+struct Health(u32);
+
+struct Frozen;
+
+fn populate(storage_type: StorageType) -> (World, Duration)
+{
+    let mut world = World::new()
+        .register_with::<Position>(storage_type)
+        .register_with::<Velocity>(storage_type)
+        .register_with::<Health>(storage_type)
+        .register_with::<Frozen>(storage_type);
+
+    let start = Instant::now();
+
+    for index in 0..ENTITIES
+    {
+        let mut builder = world
+            .spawn()
+            .with(Position(index as f64, 0.0))
+            .with(Velocity(1.0, 0.5));
+
+        if index % 2 == 0
+        {
+            builder = builder.with(Health(100));
+        }
+        if index % 10 == 0
+        {
+            builder.with(Frozen);
+        }
+    }
+    (world, start.elapsed())
+}
+
+fn average(mut f: impl FnMut()) -> Duration
+{
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS
+    {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn bench(name: &str, storage_type: StorageType)
+{
+    let (world, spawn) = populate(storage_type);
+
+    let movement = average(|| {
+        for (mut position, velocity) in &world.query::<(&mut Position, &Velocity)>()
+        {
+            position.0 += velocity.0;
+            position.1 += velocity.1;
+        }
+    });
+
+    let filtered = average(|| {
+        let query = world.query_filtered::<(&Position, &Health), With<Frozen>>();
+        black_box(
+            query
+                .iter()
+                .map(|(position, health)| position.0 + health.0 as f64)
+                .sum::<f64>(),
+        );
+    });
+
+    println!(
+        "{name:>10}: spawn {spawn:>10.2?}, movement {movement:>10.2?}, filtered {filtered:>10.2?}"
+    );
+}
+
+fn main()
+{
+    println!("{ENTITIES} entities, average of {ITERATIONS} iterations");
+
+    bench("sparse set", StorageType::SparseSet);
+    bench("table", StorageType::Table);
+}
@@ -0,0 +1,252 @@
+use std::mem;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::entity::Entity;
+
+#[derive(Debug, Default)]
+pub(crate) struct EntityAllocator
+{
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    reserved: Arc<Reserved>,
+}
+
+/// Hands out fresh indices without borrowing the allocator, the reserved entities become alive
+/// once the allocator is flushed. The freed indices are recycled only by `allocate`.
+#[derive(Clone, Debug)]
+pub(crate) struct EntityReserver
+{
+    reserved: Arc<Reserved>,
+}
+
+/// Shared by the allocator with its reservers.
+#[derive(Debug, Default)]
+struct Reserved
+{
+    /// The next fresh index.
+    next: AtomicU32,
+    /// Reserved indices given back before they were flushed, freed by the next flush.
+    released: Mutex<Vec<u32>>,
+}
+
+impl EntityAllocator
+{
+    pub fn allocate(&mut self) -> Entity
+    {
+        self.flush();
+
+        if let Some(index) = self.free.pop()
+        {
+            let slot = index as usize;
+            self.alive[slot] = true;
+
+            return Entity::new(index, self.generations[slot]);
+        }
+
+        let entity = reserve(&self.reserved.next);
+        self.flush();
+
+        entity
+    }
+
+    /// Makes the reserved entities alive, except the released ones which are freed instead.
+    pub fn flush(&mut self)
+    {
+        let next = self.reserved.next.load(Ordering::Acquire) as usize;
+
+        self.generations.resize(next, 0);
+        self.alive.resize(next, true);
+
+        let released = mem::take(&mut *self.reserved.released.lock().unwrap());
+
+        for index in released
+        {
+            self.deallocate(Entity::new(index, 0));
+        }
+    }
+
+    pub fn reserver(&self) -> EntityReserver
+    {
+        EntityReserver {
+            reserved: self.reserved.clone(),
+        }
+    }
+
+    /// Returns `true` if the `reserver` was handed out by this allocator.
+    pub fn owns(&self, reserver: &EntityReserver) -> bool
+    {
+        Arc::ptr_eq(&self.reserved, &reserver.reserved)
+    }
+
+    /// Number of indices ever handed out, alive or not.
+    pub fn capacity(&self) -> usize
+    {
+        self.alive.len()
+    }
+
+    pub fn deallocate(&mut self, entity: Entity) -> bool
+    {
+        if !self.is_alive(entity)
+        {
+            return false;
+        }
+
+        let slot = entity.index();
+
+        // The generation wraps around after 2^32 despawns, that is a risk worth taking.
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.alive[slot] = false;
+        self.free.push(slot as u32);
+
+        true
+    }
+
+    /// Alive entity stored under the index.
+    pub fn get(&self, index: usize) -> Option<Entity>
+    {
+        let alive = self.alive.get(index).copied().unwrap_or(false);
+
+        alive.then(|| Entity::new(index as u32, self.generations[index]))
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool
+    {
+        let slot = entity.index();
+
+        self.alive.get(slot).copied().unwrap_or(false)
+            && self.generations[slot] == entity.generation()
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.alive.len() - self.free.len()
+    }
+}
+
+impl EntityReserver
+{
+    #[inline]
+    pub fn reserve(&self) -> Entity
+    {
+        reserve(&self.reserved.next)
+    }
+
+    /// Gives back a reserved entity which has not been flushed yet, so the next flush frees it
+    /// instead of making it alive.
+    pub fn release(&self, entity: Entity)
+    {
+        self.reserved
+            .released
+            .lock()
+            .unwrap()
+            .push(entity.index() as u32);
+    }
+}
+
+fn reserve(next: &AtomicU32) -> Entity
+{
+    let index = next
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |next| {
+            next.checked_add(1)
+        })
+        .expect("Too many entities spawned.");
+
+    Entity::new(index, 0)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn allocate_fresh_indices()
+    {
+        let mut allocator = EntityAllocator::default();
+
+        let first = allocator.allocate();
+        let second = allocator.allocate();
+
+        assert_eq!(first, Entity::new(0, 0));
+        assert_eq!(second, Entity::new(1, 0));
+        assert_eq!(allocator.len(), 2);
+    }
+
+    #[test]
+    fn recycle_index_with_new_generation()
+    {
+        let mut allocator = EntityAllocator::default();
+
+        let first = allocator.allocate();
+        allocator.allocate();
+
+        assert!(allocator.deallocate(first));
+
+        let recycled = allocator.allocate();
+
+        assert_eq!(recycled, Entity::new(0, 1));
+        assert!(!allocator.is_alive(first));
+        assert!(allocator.is_alive(recycled));
+    }
+
+    #[test]
+    fn deallocate_twice_does_nothing()
+    {
+        let mut allocator = EntityAllocator::default();
+
+        let entity = allocator.allocate();
+
+        assert!(allocator.deallocate(entity));
+        assert!(!allocator.deallocate(entity));
+        assert_eq!(allocator.len(), 0);
+    }
+
+    #[test]
+    fn reserved_entities_are_alive_after_flush()
+    {
+        let mut allocator = EntityAllocator::default();
+
+        let freed = allocator.allocate();
+        allocator.deallocate(freed);
+
+        let reserved = allocator.reserver().reserve();
+
+        assert_eq!(reserved, Entity::new(1, 0));
+        assert!(!allocator.is_alive(reserved));
+
+        let allocated = allocator.allocate();
+
+        assert_eq!(allocated, Entity::new(0, 1));
+        assert!(allocator.is_alive(reserved));
+        assert_eq!(allocator.allocate(), Entity::new(2, 0));
+        assert_eq!(allocator.len(), 3);
+    }
+
+    #[test]
+    fn released_entities_are_freed_by_flush()
+    {
+        let mut allocator = EntityAllocator::default();
+        let reserver = allocator.reserver();
+
+        let released = reserver.reserve();
+        let kept = reserver.reserve();
+        reserver.release(released);
+
+        allocator.flush();
+
+        assert!(!allocator.is_alive(released));
+        assert!(allocator.is_alive(kept));
+        assert_eq!(allocator.len(), 1);
+        assert_eq!(allocator.allocate(), Entity::new(0, 1));
+    }
+
+    #[test]
+    fn unknown_entity_is_not_alive()
+    {
+        let allocator = EntityAllocator::default();
+
+        assert!(!allocator.is_alive(Entity::new(3, 0)));
+    }
+}
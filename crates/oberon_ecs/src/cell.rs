@@ -0,0 +1,375 @@
+use std::any::type_name;
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+const WRITING: usize = usize::MAX;
+
+/// `RefCell` which can be shared between threads. The borrows are tracked by an atomic counter
+/// instead of a lock, a conflicting borrow panics instead of waiting.
+pub struct AtomicRefCell<T>
+{
+    borrows: BorrowFlag,
+    value: UnsafeCell<T>,
+}
+
+/// Counter of the borrows of a value, shared by an `AtomicRefCell` and a whole table column.
+#[derive(Debug, Default)]
+pub(crate) struct BorrowFlag(AtomicUsize);
+
+/// Shared borrow of an `AtomicRefCell` value.
+pub struct Ref<'a, T>
+{
+    value: &'a T,
+    borrows: Option<&'a BorrowFlag>,
+}
+
+/// Exclusive borrow of an `AtomicRefCell` value.
+pub struct RefMut<'a, T>
+{
+    value: &'a mut T,
+    borrows: &'a BorrowFlag,
+}
+
+/// Tick of the change detection which can be updated through a shared reference.
+#[derive(Debug, Default)]
+pub struct TickCell(AtomicU64);
+
+// The borrows counter guarantees that the value is never aliased mutably.
+unsafe impl<T: Send> Send for AtomicRefCell<T> {}
+unsafe impl<T: Send + Sync> Sync for AtomicRefCell<T> {}
+
+impl<T> AtomicRefCell<T>
+{
+    pub const fn new(value: T) -> Self
+    {
+        Self {
+            borrows: BorrowFlag::new(),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T
+    {
+        self.value.into_inner()
+    }
+
+    /// Panics if the value is borrowed.
+    pub fn replace(&self, value: T) -> T
+    {
+        std::mem::replace(&mut *self.borrow_mut(), value)
+    }
+
+    /// Returns `None` if the value is borrowed mutably.
+    pub fn try_borrow(&self) -> Option<Ref<'_, T>>
+    {
+        self.borrows.try_share().then(|| Ref {
+            // SAFETY: The counter excludes the mutable borrows until the `Ref` is dropped.
+            value: unsafe { &*self.value.get() },
+            borrows: Some(&self.borrows),
+        })
+    }
+
+    /// Returns `None` if the value is borrowed.
+    pub fn try_borrow_mut(&self) -> Option<RefMut<'_, T>>
+    {
+        self.borrows.try_exclusive().then(|| RefMut {
+            // SAFETY: The counter excludes any other borrow until the `RefMut` is dropped.
+            value: unsafe { &mut *self.value.get() },
+            borrows: &self.borrows,
+        })
+    }
+
+    /// Panics if the value is borrowed mutably.
+    pub fn borrow(&self) -> Ref<'_, T>
+    {
+        self.try_borrow()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed mutably.", type_name::<T>()))
+    }
+
+    /// Panics if the value is borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T>
+    {
+        self.try_borrow_mut()
+            .unwrap_or_else(|| panic!("`{}` is already borrowed.", type_name::<T>()))
+    }
+}
+
+impl<T: Debug> Debug for AtomicRefCell<T>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+    {
+        match self.try_borrow()
+        {
+            Some(value) => f.debug_tuple("AtomicRefCell").field(&*value).finish(),
+            None => f.write_str("AtomicRefCell(<borrowed>)"),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for AtomicRefCell<T>
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        *self.borrow() == *other.borrow()
+    }
+}
+
+impl BorrowFlag
+{
+    pub const fn new() -> Self
+    {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// Adds a shared borrow, returns `false` if there is an exclusive one.
+    pub fn try_share(&self) -> bool
+    {
+        let mut current = self.0.load(Ordering::Relaxed);
+
+        loop
+        {
+            if current >= WRITING - 1
+            {
+                return false;
+            }
+
+            match self.0.compare_exchange_weak(
+                current,
+                current + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Takes the exclusive borrow, returns `false` if there is any other borrow.
+    #[inline]
+    pub fn try_exclusive(&self) -> bool
+    {
+        self.0
+            .compare_exchange(0, WRITING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Like `try_exclusive`, without the atomic read-modify-write. Only one thread may claim the
+    /// flag at a time, the borrow can be released from any thread.
+    #[inline]
+    pub fn try_claim(&self) -> bool
+    {
+        if self.0.load(Ordering::Acquire) != 0
+        {
+            return false;
+        }
+        self.0.store(WRITING, Ordering::Relaxed);
+        true
+    }
+
+    #[inline]
+    pub fn release_shared(&self)
+    {
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn release_exclusive(&self)
+    {
+        self.0.store(0, Ordering::Release);
+    }
+}
+
+impl<'a, T> Ref<'a, T>
+{
+    /// Shared borrow released on drop, or kept by its owner if the `borrows` are `None`.
+    #[inline]
+    pub(crate) fn new(value: &'a T, borrows: Option<&'a BorrowFlag>) -> Self
+    {
+        Self { value, borrows }
+    }
+}
+
+impl<T> Deref for Ref<'_, T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        self.value
+    }
+}
+
+impl<T> Drop for Ref<'_, T>
+{
+    #[inline]
+    fn drop(&mut self)
+    {
+        if let Some(borrows) = self.borrows
+        {
+            borrows.release_shared();
+        }
+    }
+}
+
+impl<T: Debug> Debug for Ref<'_, T>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+    {
+        self.value.fmt(f)
+    }
+}
+
+impl<'a, T> RefMut<'a, T>
+{
+    /// Exclusive borrow released on drop.
+    #[inline]
+    pub(crate) fn new(value: &'a mut T, borrows: &'a BorrowFlag) -> Self
+    {
+        Self { value, borrows }
+    }
+}
+
+impl<T> Deref for RefMut<'_, T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        self.value
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target
+    {
+        self.value
+    }
+}
+
+impl<T> Drop for RefMut<'_, T>
+{
+    #[inline]
+    fn drop(&mut self)
+    {
+        self.borrows.release_exclusive();
+    }
+}
+
+impl<T: Debug> Debug for RefMut<'_, T>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result
+    {
+        self.value.fmt(f)
+    }
+}
+
+impl TickCell
+{
+    #[inline]
+    pub const fn new(tick: u64) -> Self
+    {
+        Self(AtomicU64::new(tick))
+    }
+
+    #[inline]
+    pub fn get(&self) -> u64
+    {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn set(&self, tick: u64)
+    {
+        self.0.store(tick, Ordering::Relaxed);
+    }
+
+    /// Advances the tick, returns the new value.
+    #[inline]
+    pub fn increment(&self) -> u64
+    {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn many_shared_borrows()
+    {
+        let cell = AtomicRefCell::new(1);
+        let first = cell.borrow();
+        let second = cell.borrow();
+
+        assert_eq!(*first + *second, 2);
+        assert!(cell.try_borrow_mut().is_none());
+    }
+
+    #[test]
+    fn borrow_again_after_release()
+    {
+        let cell = AtomicRefCell::new(1);
+
+        *cell.borrow_mut() += 1;
+        drop(cell.borrow());
+
+        assert_eq!(*cell.borrow_mut(), 2);
+        assert_eq!(cell.into_inner(), 2);
+    }
+
+    #[test]
+    fn mutable_borrow_is_exclusive()
+    {
+        let cell = AtomicRefCell::new(1);
+        let value = cell.borrow_mut();
+
+        assert!(cell.try_borrow().is_none());
+        assert!(cell.try_borrow_mut().is_none());
+        drop(value);
+        assert!(cell.try_borrow().is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_when_borrowed_mutably_twice()
+    {
+        let cell = AtomicRefCell::new(1);
+        let _first = cell.borrow_mut();
+        let _second = cell.borrow_mut();
+    }
+
+    #[test]
+    fn shared_between_threads()
+    {
+        let cell = AtomicRefCell::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4
+            {
+                scope.spawn(|| {
+                    loop
+                    {
+                        if let Some(mut value) = cell.try_borrow_mut()
+                        {
+                            *value += 1;
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(cell.into_inner(), 4);
+    }
+}
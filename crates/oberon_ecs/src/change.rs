@@ -0,0 +1,333 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::cell::{RefMut, TickCell};
+use crate::entity::Entity;
+use crate::query::{Access, QueryFilter};
+use crate::storage::{ArchetypeStorage, Candidate, Storage};
+use crate::system::SystemParam;
+use crate::table::Archetype;
+use crate::world::World;
+
+/// Ticks of the world clock a query or a system sees. Everything stamped after `last_run` counts
+/// as new, the writes are stamped with `this_run`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ticks
+{
+    pub last_run: u64,
+    pub this_run: u64,
+}
+
+/// Mutable borrow of a component which marks it as changed on the first write.
+pub struct Mut<'w, T>
+{
+    value: RefMut<'w, T>,
+    changed: &'w TickCell,
+    tick: u64,
+}
+
+/// Matches the entities which got the `T` component since the last run.
+pub struct Added<T>(PhantomData<T>);
+
+/// Matches the entities whose `T` component was added or written to since the last run.
+pub struct Changed<T>(PhantomData<T>);
+
+/// Entities which lost the `T` component, or were despawned, since the last run of the system.
+pub struct RemovedComponents<'w, T: 'static>
+{
+    storage: Option<Storage<'w, T>>,
+    last_run: u64,
+}
+
+impl Ticks
+{
+    #[inline]
+    pub const fn is_newer(&self, tick: u64) -> bool
+    {
+        tick > self.last_run
+    }
+}
+
+impl<'w, T> Mut<'w, T>
+{
+    pub(crate) fn new(value: RefMut<'w, T>, changed: &'w TickCell, tick: u64) -> Self
+    {
+        Self {
+            value,
+            changed,
+            tick,
+        }
+    }
+}
+
+impl<T> Deref for Mut<'_, T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target
+    {
+        self.changed.set(self.tick);
+        &mut self.value
+    }
+}
+
+impl<T> RemovedComponents<'_, T>
+{
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_
+    {
+        self.storage
+            .into_iter()
+            .flat_map(|storage| storage.removed_since(self.last_run))
+    }
+}
+
+impl<T: 'static> QueryFilter for Added<T>
+{
+    type Fetch<'w> = (Storage<'w, T>, Ticks);
+    type Rows<'w> = (ArchetypeStorage<'w, T>, Ticks);
+
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        world.storage::<T>().map(|storage| (storage, ticks))
+    }
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+    {
+        Candidate::narrow(driver, fetch.0.candidate());
+    }
+
+    fn matches((storage, ticks): &Self::Fetch<'_>, entity: Entity) -> bool
+    {
+        storage
+            .get(entity)
+            .is_some_and(|entry| ticks.is_newer(entry.added.get()))
+    }
+
+    fn rows<'w>(
+        (storage, ticks): &Self::Fetch<'w>, archetype: &'w Archetype,
+    ) -> Option<Self::Rows<'w>>
+    {
+        storage.in_archetype(archetype).map(|rows| (rows, *ticks))
+    }
+
+    fn matches_row((rows, ticks): &Self::Rows<'_>, entity: Entity, row: usize) -> bool
+    {
+        rows.get(entity, row)
+            .is_some_and(|entry| ticks.is_newer(entry.added.get()))
+    }
+}
+
+impl<T: 'static> QueryFilter for Changed<T>
+{
+    type Fetch<'w> = (Storage<'w, T>, Ticks);
+    type Rows<'w> = (ArchetypeStorage<'w, T>, Ticks);
+
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        world.storage::<T>().map(|storage| (storage, ticks))
+    }
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+    {
+        Candidate::narrow(driver, fetch.0.candidate());
+    }
+
+    fn matches((storage, ticks): &Self::Fetch<'_>, entity: Entity) -> bool
+    {
+        storage
+            .get(entity)
+            .is_some_and(|entry| ticks.is_newer(entry.changed.get()))
+    }
+
+    fn rows<'w>(
+        (storage, ticks): &Self::Fetch<'w>, archetype: &'w Archetype,
+    ) -> Option<Self::Rows<'w>>
+    {
+        storage.in_archetype(archetype).map(|rows| (rows, *ticks))
+    }
+
+    fn matches_row((rows, ticks): &Self::Rows<'_>, entity: Entity, row: usize) -> bool
+    {
+        rows.get(entity, row)
+            .is_some_and(|entry| ticks.is_newer(entry.changed.get()))
+    }
+}
+
+impl<T: 'static> SystemParam for RemovedComponents<'_, T>
+{
+    type Item<'w, 's> = RemovedComponents<'w, T>;
+    type State = ();
+
+    fn access(_: &mut Access) {}
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, ticks: Ticks) -> Self::Item<'w, 's>
+    {
+        RemovedComponents {
+            storage: world.storage::<T>(),
+            last_run: ticks.last_run,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::query::Query;
+    use crate::resource::ResMut;
+    use crate::schedule::{IntoSystemConfig, Schedule, Stage};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Default)]
+    struct Seen(Vec<i32>);
+
+    fn world() -> World
+    {
+        let mut world = World::new().register::<Position>();
+        world.insert_resource(Seen::default());
+        world
+    }
+
+    fn count<F: QueryFilter>(world: &World) -> usize
+    {
+        world.query_filtered::<&Position, F>().iter().count()
+    }
+
+    fn track_changed(query: Query<&Position, Changed<Position>>, mut seen: ResMut<Seen>)
+    {
+        seen.0.extend(query.iter().map(|position| position.0));
+    }
+
+    #[test]
+    fn world_query_sees_changes_of_the_current_frame()
+    {
+        let mut world = world();
+        let first = world.spawn().with(Position(1)).into_id();
+        world.spawn().with(Position(2));
+
+        assert_eq!(count::<Added<Position>>(&world), 2);
+
+        world.clear_trackers();
+
+        assert_eq!(count::<Changed<Position>>(&world), 0);
+
+        world.get_mut::<Position>(first).unwrap().0 = 10;
+
+        let changed = world.query_filtered::<&Position, Changed<Position>>();
+        let changed: Vec<_> = changed.iter().map(|position| position.0).collect();
+
+        assert_eq!(changed, vec![10]);
+        assert_eq!(count::<Added<Position>>(&world), 0);
+    }
+
+    #[test]
+    fn reading_mutable_borrow_does_not_mark_change()
+    {
+        let mut world = world();
+        let entity = world.spawn().with(Position(1)).into_id();
+        world.clear_trackers();
+
+        let value = world.get_mut::<Position>(entity).unwrap();
+        assert_eq!(*value, Position(1));
+        drop(value);
+
+        assert_eq!(count::<Changed<Position>>(&world), 0);
+    }
+
+    #[test]
+    fn system_sees_changes_since_its_last_run()
+    {
+        fn spawn(world: &mut World)
+        {
+            world.spawn().with(Position(1));
+            world.spawn().with(Position(2));
+        }
+
+        fn move_first(query: Query<&mut Position>)
+        {
+            if let Some(mut position) = query.iter().find(|position| position.0 % 2 == 1)
+            {
+                position.0 += 2;
+            }
+        }
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Startup, spawn)
+            .add_system(Stage::Update, track_changed.label("track"))
+            .add_system(Stage::PostUpdate, move_first);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<Seen>().unwrap().0, vec![1, 2, 3, 5]);
+    }
+
+    #[test]
+    fn changes_made_after_the_system_are_seen_on_next_run()
+    {
+        fn touch(query: Query<&mut Position>)
+        {
+            query.iter().for_each(|mut position| position.0 += 1);
+        }
+
+        let mut world = world();
+        world.spawn().with(Position(0));
+
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Update, track_changed.label("track"))
+            .add_system(Stage::Update, touch.after("track"));
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<Seen>().unwrap().0, vec![0, 1]);
+    }
+
+    #[test]
+    fn removed_components_are_reported_once()
+    {
+        fn despawn_all(world: &mut World)
+        {
+            let entities: Vec<_> = world.query::<Entity>().iter().collect();
+
+            for entity in entities
+            {
+                world.despawn(entity);
+            }
+        }
+
+        fn count_removed(removed: RemovedComponents<Position>, mut seen: ResMut<Seen>)
+        {
+            seen.0.push(removed.iter().count() as i32);
+        }
+
+        let mut world = world();
+        world.spawn().with(Position(0));
+        world.spawn().with(Position(1));
+
+        let mut schedule = Schedule::new()
+            .add_system(Stage::PreUpdate, despawn_all)
+            .add_system(Stage::Update, count_removed);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<Seen>().unwrap().0, vec![2, 0]);
+    }
+}
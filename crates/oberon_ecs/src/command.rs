@@ -0,0 +1,338 @@
+use crate::allocator::EntityReserver;
+use crate::change::Ticks;
+use crate::entity::{Entity, EntityBuilder};
+use crate::query::Access;
+use crate::system::SystemParam;
+use crate::world::World;
+
+type Insert = Box<dyn FnOnce(EntityBuilder<'_>) -> EntityBuilder<'_> + Send>;
+
+/// Changes of the world recorded while it is borrowed, applied later by `apply`. A system taking
+/// `&mut Commands` gets its own buffer, applied by the schedule once the whole stage has run.
+pub struct Commands
+{
+    commands: Vec<Command>,
+    reserver: EntityReserver,
+}
+
+/// Records the components of an entity spawned by `Commands::spawn`.
+pub struct EntityCommands<'a>
+{
+    entity: Entity,
+    inserts: &'a mut Vec<Insert>,
+}
+
+enum Command
+{
+    Spawn(Entity, Vec<Insert>),
+    Despawn(Entity),
+    Insert(Entity, Insert),
+    Remove(Entity, fn(&mut World, Entity)),
+}
+
+impl Commands
+{
+    /// Commands for the `world`, applying them to any other one panics.
+    pub fn new(world: &World) -> Self
+    {
+        Self {
+            commands: Vec::new(),
+            reserver: world.entities().reserver(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.commands.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.commands.len()
+    }
+
+    /// Reserves the entity right away, so it can be referred to by the other commands. The
+    /// entity becomes alive once the commands are applied, dropping the commands without applying
+    /// them frees it again.
+    pub fn spawn(&mut self) -> EntityCommands<'_>
+    {
+        let entity = self.reserver.reserve();
+        self.commands.push(Command::Spawn(entity, Vec::new()));
+
+        match self.commands.last_mut()
+        {
+            Some(Command::Spawn(entity, inserts)) => EntityCommands {
+                entity: *entity,
+                inserts,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Despawns the entity together with its descendants.
+    pub fn despawn(&mut self, entity: Entity)
+    {
+        self.commands.push(Command::Despawn(entity));
+    }
+
+    /// Adds or replaces the entity component, nothing happens if the entity is dead by then.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, entity: Entity, component: T)
+    {
+        self.commands
+            .push(Command::Insert(entity, insert(component)));
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity)
+    {
+        self.commands.push(Command::Remove(entity, |world, entity| {
+            world.remove::<T>(entity);
+        }));
+    }
+
+    /// Applies the commands in the order they were recorded, panics if they were created for
+    /// another world.
+    pub fn apply(&mut self, world: &mut World)
+    {
+        assert!(
+            world.entities().owns(&self.reserver),
+            "Commands applied to a world they were not created for."
+        );
+        world.flush_entities();
+
+        for command in self.commands.drain(..)
+        {
+            match command
+            {
+                Command::Spawn(entity, inserts) =>
+                {
+                    if let Some(builder) = world.entity_mut(entity)
+                    {
+                        inserts
+                            .into_iter()
+                            .fold(builder, |builder, insert| insert(builder));
+                    }
+                }
+                Command::Despawn(entity) =>
+                {
+                    world.despawn(entity);
+                }
+                Command::Insert(entity, insert) =>
+                {
+                    if let Some(builder) = world.entity_mut(entity)
+                    {
+                        insert(builder);
+                    }
+                }
+                Command::Remove(entity, remove) => remove(world, entity),
+            }
+        }
+    }
+}
+
+impl Drop for Commands
+{
+    fn drop(&mut self)
+    {
+        for command in &self.commands
+        {
+            if let Command::Spawn(entity, _) = command
+            {
+                self.reserver.release(*entity);
+            }
+        }
+    }
+}
+
+impl EntityCommands<'_>
+{
+    #[inline]
+    pub fn id(&self) -> Entity
+    {
+        self.entity
+    }
+
+    pub fn with<T: Send + Sync + 'static>(self, component: T) -> Self
+    {
+        self.inserts.push(insert(component));
+        self
+    }
+}
+
+fn insert<T: Send + Sync + 'static>(component: T) -> Insert
+{
+    Box::new(move |builder| builder.with(component))
+}
+
+impl SystemParam for &mut Commands
+{
+    type Item<'w, 's> = &'s mut Commands;
+    type State = Commands;
+
+    fn access(_: &mut Access) {}
+
+    fn init(world: &mut World) -> Self::State
+    {
+        Commands::new(world)
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, _: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        state
+    }
+
+    fn apply(state: &mut Self::State, world: &mut World)
+    {
+        state.apply(world);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::query::Query;
+    use crate::schedule::{Schedule, Stage};
+
+    #[derive(Debug, PartialEq)]
+    struct Health(i32);
+
+    struct Bullet;
+
+    fn world() -> World
+    {
+        World::new().register::<Health>().register::<Bullet>()
+    }
+
+    #[test]
+    fn record_changes_while_iterating()
+    {
+        let mut world = world();
+        world.spawn().with(Health(0));
+        let alive = world.spawn().with(Health(3)).into_id();
+
+        let mut commands = Commands::new(&world);
+
+        world.for_each::<Health>(|entity, health| {
+            if health.0 <= 0
+            {
+                commands.despawn(entity);
+            }
+            else
+            {
+                commands.spawn().with(Bullet).with(Health(1));
+                commands.insert(entity, Health(health.0 - 1));
+            }
+        });
+
+        assert_eq!(commands.len(), 3);
+        assert_eq!(world.len(), 2);
+
+        commands.apply(&mut world);
+
+        assert!(commands.is_empty());
+        assert_eq!(world.len(), 2);
+        assert_eq!(*world.get::<Health>(alive).unwrap(), Health(2));
+        assert_eq!(world.query::<&Bullet>().iter().count(), 1);
+    }
+
+    #[test]
+    fn refer_to_spawned_entities_before_they_are_applied()
+    {
+        let mut world = world();
+        let mut commands = Commands::new(&world);
+
+        let bullet = commands.spawn().with(Bullet).id();
+        commands.insert(bullet, Health(5));
+
+        assert!(!world.is_alive(bullet));
+
+        let other = world.spawn().into_id();
+        commands.apply(&mut world);
+
+        assert_ne!(bullet, other);
+        assert_eq!(world.len(), 2);
+        assert_eq!(*world.get::<Health>(bullet).unwrap(), Health(5));
+        assert!(world.get::<Bullet>(bullet).is_some());
+    }
+
+    #[test]
+    fn remove_component_and_skip_dead_entities()
+    {
+        let mut world = world();
+        let first = world.spawn().with(Health(1)).with(Bullet).into_id();
+        let second = world.spawn().with(Health(1)).into_id();
+
+        let mut commands = Commands::new(&world);
+        commands.remove::<Bullet>(first);
+        commands.despawn(second);
+        commands.insert(second, Bullet);
+        commands.apply(&mut world);
+
+        assert!(world.get::<Bullet>(first).is_none());
+        assert!(world.get::<Health>(first).is_some());
+        assert!(!world.is_alive(second));
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    fn dropped_commands_free_their_entities()
+    {
+        let mut world = world();
+        let mut commands = Commands::new(&world);
+        let bullet = commands.spawn().with(Bullet).id();
+
+        drop(commands);
+
+        let entity = world.spawn().into_id();
+
+        assert!(!world.is_alive(bullet));
+        assert_eq!(entity.index(), bullet.index());
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "not created for")]
+    fn refuse_another_world()
+    {
+        let world = world();
+        let mut other = World::new();
+
+        let mut commands = Commands::new(&world);
+        commands.spawn();
+        commands.apply(&mut other);
+    }
+
+    #[test]
+    fn system_commands_are_applied_after_the_stage()
+    {
+        fn shoot(query: Query<&Health>, commands: &mut Commands)
+        {
+            for _ in &query
+            {
+                commands.spawn().with(Bullet);
+            }
+        }
+
+        fn count(query: Query<&Bullet>, commands: &mut Commands)
+        {
+            let bullets = query.iter().count() as i32;
+            commands.spawn().with(Health(bullets));
+        }
+
+        let mut world = world();
+        world.spawn().with(Health(1));
+
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Update, shoot)
+            .add_system(Stage::Update, count);
+
+        schedule.run(&mut world);
+
+        let health: Vec<_> = world.query::<&Health>().iter().map(|h| h.0).collect();
+
+        assert_eq!(health, vec![1, 0]);
+        assert_eq!(world.query::<&Bullet>().iter().count(), 1);
+    }
+}
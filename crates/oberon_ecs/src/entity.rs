@@ -1,54 +1,91 @@
-use std::any::type_name;
-use std::cell::RefCell;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
 use crate::world::World;
 
-#[derive(Debug)]
-pub struct Entity<T>
+/// Handle of a spawned entity. The generation changes every time the index gets recycled, so the
+/// handles of despawned entities never point to the new ones.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Entity
 {
-    pub id: usize,
-    pub item: RefCell<T>,
+    index: u32,
+    generation: u32,
 }
 
 pub struct EntityBuilder<'a>
 {
-    id: usize,
+    entity: Entity,
     world: &'a mut World,
 }
 
-impl<T> Entity<T>
+impl Entity
 {
-    pub const fn new(id: usize, item: T) -> Self
+    pub(crate) const fn new(index: u32, generation: u32) -> Self
     {
-        let item = RefCell::new(item);
-        Self { id, item }
+        Self { index, generation }
+    }
+
+    #[inline]
+    pub const fn generation(&self) -> u32
+    {
+        self.generation
+    }
+
+    #[inline]
+    pub const fn index(&self) -> usize
+    {
+        self.index as usize
+    }
+}
+
+impl Display for Entity
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+    {
+        write!(f, "{}v{}", self.index, self.generation)
     }
 }
 
 impl<'a> EntityBuilder<'a>
 {
-    pub fn new(id: usize, world: &'a mut World) -> Self
+    pub fn new(entity: Entity, world: &'a mut World) -> Self
+    {
+        Self { entity, world }
+    }
+
+    /// Adds or replaces the component, registers its type in a sparse set if it is not registered
+    /// yet. Panics if the component is a `Parent` or `Children` making a cycle, see
+    /// `World::insert`.
+    pub fn with<T: Send + Sync + 'static>(self, component: T) -> Self
+    {
+        if let Err(error) = self.world.try_insert(self.entity, component)
+        {
+            panic!("{error}");
+        }
+        self
+    }
+
+    /// Takes the component out of the entity, see `World::remove`.
+    pub fn remove<T: 'static>(self) -> Self
     {
-        Self { id, world }
+        self.world.remove::<T>(self.entity);
+        self
     }
 
-    pub fn with<T: 'static>(self, component: T) -> Self
+    /// Attaches the entity to the `parent`, panics if the `parent` is not alive or it is the
+    /// entity itself or one of its descendants.
+    pub fn with_parent(self, parent: Entity) -> Self
     {
-        if let Some(storage) = self.world.get_storage_mut::<T>()
+        if let Err(error) = self.world.try_set_parent(self.entity, parent)
         {
-            storage.add(self.id, component);
-            return self;
+            panic!("{error}");
         }
-        panic!(
-            "You cannot spawn an entity `{}` with unregistered type `{}`.",
-            self.id,
-            type_name::<T>()
-        );
+        self
     }
 
     #[inline]
-    pub fn into_id(self) -> usize
+    pub fn into_id(self) -> Entity
     {
-        self.id
+        self.entity
     }
 }
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::entity::Entity;
+
+/// Failure of the fallible `try_*` variants of the world and query methods.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EcsError
+{
+    /// The component type was never registered nor inserted.
+    UnregisteredComponent(&'static str),
+    /// The entity was despawned.
+    DeadEntity(Entity),
+    /// The entity is alive, but it does not have the component.
+    MissingComponent
+    {
+        entity: Entity,
+        component: &'static str,
+    },
+    MissingResource(&'static str),
+    /// The parent is the entity itself or one of its descendants.
+    HierarchyCycle
+    {
+        child: Entity,
+        parent: Entity,
+    },
+    /// The component or resource is borrowed mutably elsewhere, or it is borrowed elsewhere and
+    /// a mutable borrow was requested.
+    BorrowConflict(&'static str),
+}
+
+impl Display for EcsError
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+    {
+        match self
+        {
+            Self::UnregisteredComponent(component) =>
+            {
+                write!(f, "Component `{component}` is not registered.")
+            }
+            Self::DeadEntity(entity) => write!(f, "Entity `{entity}` is not alive."),
+            Self::MissingComponent { entity, component } =>
+            {
+                write!(
+                    f,
+                    "Entity `{entity}` does not have component `{component}`."
+                )
+            }
+            Self::MissingResource(resource) => write!(f, "Resource `{resource}` does not exist."),
+            Self::HierarchyCycle { child, parent } =>
+            {
+                write!(
+                    f,
+                    "Entity `{parent}` cannot be the parent of its ancestor `{child}`."
+                )
+            }
+            Self::BorrowConflict(name) => write!(f, "`{name}` is already borrowed."),
+        }
+    }
+}
+
+impl Error for EcsError {}
@@ -0,0 +1,294 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use crate::cell::{Ref, RefMut};
+use crate::change::Ticks;
+use crate::query::Access;
+use crate::system::SystemParam;
+use crate::world::World;
+
+/// Double buffered queue of `T` events, stored as a resource. Every update drops the events sent
+/// before the previous update, so each event can be read during the frame it was sent in and
+/// during the next one.
+#[derive(Debug)]
+pub struct Events<T>
+{
+    older: Vec<T>,
+    newer: Vec<T>,
+    older_start: usize,
+    newer_start: usize,
+}
+
+/// Position of a reader in the events queue, every reader keeps its own.
+#[derive(Debug)]
+pub struct EventCursor<T>
+{
+    read: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// Reads the events not seen by the system yet.
+pub struct EventReader<'w, 's, T: 'static>
+{
+    events: Ref<'w, Events<T>>,
+    cursor: &'s mut EventCursor<T>,
+}
+
+pub struct EventWriter<'w, T: 'static>
+{
+    events: RefMut<'w, Events<T>>,
+}
+
+impl<T> Default for Events<T>
+{
+    fn default() -> Self
+    {
+        Self {
+            older: Vec::new(),
+            newer: Vec::new(),
+            older_start: 0,
+            newer_start: 0,
+        }
+    }
+}
+
+impl<T> Events<T>
+{
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// Number of the events which can still be read.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.older.len() + self.newer.len()
+    }
+
+    pub fn send(&mut self, event: T)
+    {
+        self.newer.push(event);
+    }
+
+    /// Drops the events sent before the previous update.
+    pub fn update(&mut self)
+    {
+        self.older = std::mem::take(&mut self.newer);
+        self.older_start = self.newer_start;
+        self.newer_start += self.older.len();
+    }
+
+    #[inline]
+    fn sent(&self) -> usize
+    {
+        self.newer_start + self.newer.len()
+    }
+
+    fn iter_from(&self, id: usize) -> impl Iterator<Item = &T>
+    {
+        let skip_older = id.saturating_sub(self.older_start);
+        let skip_newer = id.saturating_sub(self.newer_start);
+
+        self.older
+            .iter()
+            .skip(skip_older)
+            .chain(self.newer.iter().skip(skip_newer))
+    }
+}
+
+impl<T> Default for EventCursor<T>
+{
+    fn default() -> Self
+    {
+        Self {
+            read: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> EventCursor<T>
+{
+    /// Returns the events sent since the previous read, oldest first.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T>
+    {
+        let start = self.read;
+        self.read = events.sent();
+
+        events.iter_from(start)
+    }
+}
+
+impl<T> EventReader<'_, '_, T>
+{
+    pub fn read(&mut self) -> impl Iterator<Item = &T>
+    {
+        self.cursor.read(&self.events)
+    }
+}
+
+impl<T> EventWriter<'_, T>
+{
+    #[inline]
+    pub fn send(&mut self, event: T)
+    {
+        self.events.send(event);
+    }
+}
+
+impl<T: 'static> SystemParam for EventReader<'_, '_, T>
+{
+    type Item<'w, 's> = EventReader<'w, 's, T>;
+    type State = EventCursor<T>;
+
+    fn access(access: &mut Access)
+    {
+        access.read_resource::<Events<T>>();
+    }
+
+    fn init(_: &mut World) -> Self::State
+    {
+        EventCursor::default()
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        let events = world
+            .resource::<Events<T>>()
+            .unwrap_or_else(|| unregistered::<T>());
+
+        EventReader {
+            events,
+            cursor: state,
+        }
+    }
+}
+
+impl<T: 'static> SystemParam for EventWriter<'_, T>
+{
+    type Item<'w, 's> = EventWriter<'w, T>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.write_resource::<Events<T>>();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        let events = world
+            .resource_mut::<Events<T>>()
+            .unwrap_or_else(|| unregistered::<T>());
+
+        EventWriter { events }
+    }
+}
+
+pub(crate) fn update_events<T: 'static>(world: &World)
+{
+    if let Some(mut events) = world.resource_mut::<Events<T>>()
+    {
+        events.update();
+    }
+}
+
+fn unregistered<T>() -> !
+{
+    panic!("Event `{}` was not added to the world.", type_name::<T>());
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::resource::ResMut;
+    use crate::schedule::{IntoSystemConfig, Schedule, Stage};
+
+    #[derive(Debug, PartialEq)]
+    struct Damage(u32);
+
+    #[derive(Default)]
+    struct Total(u32);
+
+    #[test]
+    fn events_live_for_two_updates()
+    {
+        let mut events = Events::default();
+        let mut cursor = EventCursor::default();
+
+        events.send(Damage(1));
+        events.update();
+        events.send(Damage(2));
+
+        let read: Vec<_> = cursor.read(&events).collect();
+        assert_eq!(read, vec![&Damage(1), &Damage(2)]);
+
+        events.update();
+        events.update();
+
+        assert!(events.is_empty());
+        assert_eq!(cursor.read(&events).count(), 0);
+    }
+
+    #[test]
+    fn readers_have_independent_cursors()
+    {
+        let mut events = Events::default();
+        let mut first = EventCursor::default();
+        let mut second = EventCursor::default();
+
+        events.send(Damage(1));
+
+        assert_eq!(first.read(&events).count(), 1);
+
+        events.send(Damage(2));
+
+        assert_eq!(first.read(&events).collect::<Vec<_>>(), vec![&Damage(2)]);
+        assert_eq!(second.read(&events).count(), 2);
+        assert_eq!(first.read(&events).count(), 0);
+    }
+
+    #[test]
+    fn cursor_skips_dropped_events()
+    {
+        let mut events = Events::default();
+        let mut cursor = EventCursor::default();
+
+        events.send(Damage(1));
+        events.update();
+        events.update();
+        events.send(Damage(2));
+
+        assert_eq!(cursor.read(&events).collect::<Vec<_>>(), vec![&Damage(2)]);
+    }
+
+    #[test]
+    fn systems_exchange_events()
+    {
+        fn attack(mut writer: EventWriter<Damage>)
+        {
+            writer.send(Damage(3));
+        }
+
+        fn apply(mut reader: EventReader<Damage>, mut total: ResMut<Total>)
+        {
+            total.0 += reader.read().map(|damage| damage.0).sum::<u32>();
+        }
+
+        let mut world = World::new().add_event::<Damage>();
+        world.insert_resource(Total::default());
+
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Update, apply.after("attack"))
+            .add_system(Stage::Update, attack.label("attack"));
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<Total>().unwrap().0, 6);
+    }
+}
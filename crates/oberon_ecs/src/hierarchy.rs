@@ -0,0 +1,222 @@
+use std::ops::Deref;
+
+use crate::entity::Entity;
+
+/// Entity this one is attached to, kept in sync with the `Children` of the parent by
+/// `World::set_parent`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Parent(pub(crate) Entity);
+
+/// Entities attached to this one, in the order they were attached.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Children(pub(crate) Vec<Entity>);
+
+impl Parent
+{
+    #[inline]
+    pub const fn get(&self) -> Entity
+    {
+        self.0
+    }
+}
+
+impl Deref for Children
+{
+    type Target = [Entity];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::error::EcsError;
+    use crate::world::World;
+
+    fn children(world: &World, entity: Entity) -> Vec<Entity>
+    {
+        world
+            .get::<Children>(entity)
+            .map(|children| children.to_vec())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn set_parent_links_both_entities()
+    {
+        let mut world = World::new();
+        let parent = world.spawn().into_id();
+        let first = world.spawn().with_parent(parent).into_id();
+        let second = world.spawn().with_parent(parent).into_id();
+
+        assert_eq!(children(&world, parent), vec![first, second]);
+        assert_eq!(world.get::<Parent>(first).unwrap().get(), parent);
+        assert_eq!(world.get::<Parent>(second).unwrap().get(), parent);
+    }
+
+    #[test]
+    fn reparenting_detaches_from_previous_parent()
+    {
+        let mut world = World::new();
+        let first = world.spawn().into_id();
+        let second = world.spawn().into_id();
+        let child = world.spawn().with_parent(first).into_id();
+
+        assert!(world.set_parent(child, second));
+
+        assert!(world.get::<Children>(first).is_none());
+        assert_eq!(children(&world, second), vec![child]);
+        assert_eq!(world.remove_parent(child), Some(second));
+        assert!(world.get::<Parent>(child).is_none());
+        assert!(world.get::<Children>(second).is_none());
+    }
+
+    #[test]
+    fn refuse_cycles()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let child = world.spawn().with_parent(root).into_id();
+        let grandchild = world.spawn().with_parent(child).into_id();
+
+        assert!(!world.set_parent(root, grandchild));
+        assert!(!world.set_parent(root, root));
+        assert!(world.get::<Parent>(root).is_none());
+        assert_eq!(
+            world.try_set_parent(root, grandchild),
+            Err(EcsError::HierarchyCycle {
+                child: root,
+                parent: grandchild
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be the parent of its ancestor")]
+    fn with_parent_reports_cycles()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let child = world.spawn().with_parent(root).into_id();
+
+        world.entity_mut(root).unwrap().with_parent(child);
+    }
+
+    #[test]
+    fn despawn_removes_descendants()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let child = world.spawn().with_parent(root).into_id();
+        let grandchild = world.spawn().with_parent(child).into_id();
+        let other = world.spawn().into_id();
+
+        assert_eq!(world.descendants(root), vec![root, child, grandchild]);
+        assert!(world.despawn(root));
+
+        assert!(!world.is_alive(child));
+        assert!(!world.is_alive(grandchild));
+        assert!(world.is_alive(other));
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    fn despawn_child_detaches_it_from_parent()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let first = world.spawn().with_parent(root).into_id();
+        let second = world.spawn().with_parent(root).into_id();
+
+        world.despawn(first);
+
+        assert!(world.is_alive(root));
+        assert_eq!(children(&world, root), vec![second]);
+    }
+
+    #[test]
+    fn inserted_parent_links_both_entities()
+    {
+        let mut world = World::new();
+        let first = world.spawn().into_id();
+        let second = world.spawn().into_id();
+        let sibling = world.spawn().with_parent(second).into_id();
+        let child = world.spawn().with_parent(first).into_id();
+
+        let parent = *world.get::<Parent>(sibling).unwrap();
+        assert!(world.insert(child, parent));
+
+        assert!(world.get::<Children>(first).is_none());
+        assert_eq!(children(&world, second), vec![sibling, child]);
+
+        let child_parent = *world.get::<Parent>(child).unwrap();
+        assert!(!world.insert(second, child_parent));
+        assert!(world.get::<Parent>(second).is_none());
+    }
+
+    #[test]
+    fn removed_parent_detaches_the_child()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let first = world.spawn().with_parent(root).into_id();
+        let second = world.spawn().with_parent(root).into_id();
+
+        assert_eq!(world.remove::<Parent>(first), Some(Parent(root)));
+        assert_eq!(children(&world, root), vec![second]);
+
+        world.despawn(root);
+        assert!(world.is_alive(first));
+    }
+
+    #[test]
+    fn inserted_children_replace_the_previous_ones()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let old = world.spawn().with_parent(root).into_id();
+        let new = world.spawn().into_id();
+
+        world.entity_mut(root).unwrap().with(Children(vec![new]));
+
+        assert!(world.get::<Parent>(old).is_none());
+        assert_eq!(world.get::<Parent>(new).unwrap().get(), root);
+        assert_eq!(children(&world, root), vec![new]);
+
+        assert_eq!(
+            world.try_insert(new, Children(vec![root])),
+            Err(EcsError::HierarchyCycle {
+                child: root,
+                parent: new
+            })
+        );
+        assert!(world.get::<Children>(new).is_none());
+    }
+
+    #[test]
+    fn removed_children_are_detached()
+    {
+        let mut world = World::new();
+        let root = world.spawn().into_id();
+        let first = world.spawn().with_parent(root).into_id();
+        let second = world.spawn().with_parent(root).into_id();
+
+        assert_eq!(
+            world.remove::<Children>(root),
+            Some(Children(vec![first, second]))
+        );
+        assert!(world.get::<Parent>(first).is_none());
+        assert!(world.get::<Parent>(second).is_none());
+
+        world.despawn(root);
+        assert!(world.is_alive(first));
+        assert!(world.is_alive(second));
+    }
+}
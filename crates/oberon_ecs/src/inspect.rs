@@ -0,0 +1,215 @@
+use std::any::type_name;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+
+use crate::entity::Entity;
+use crate::world::World;
+
+/// Component of an inspected entity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComponentView
+{
+    pub name: &'static str,
+    /// Formatted value, `None` if the component was not made debuggable.
+    pub value: Option<String>,
+}
+
+/// Number of entities having the component.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ComponentCount
+{
+    pub name: &'static str,
+    pub count: usize,
+}
+
+/// Type erased access to a registered component, used by `World::inspect`.
+#[derive(Clone, Copy)]
+pub(crate) struct ComponentInfo
+{
+    pub name: &'static str,
+    pub contains: fn(&World, Entity) -> bool,
+    pub count: fn(&World) -> usize,
+    pub debug: Option<fn(&World, Entity) -> String>,
+}
+
+impl ComponentInfo
+{
+    pub fn new<T: 'static>() -> Self
+    {
+        Self {
+            name: type_name::<T>(),
+            contains: contains::<T>,
+            count: count::<T>,
+            debug: None,
+        }
+    }
+
+    pub fn with_debug<T: Debug + 'static>(mut self) -> Self
+    {
+        self.debug = Some(debug::<T>);
+        self
+    }
+
+    pub fn view(&self, world: &World, entity: Entity) -> ComponentView
+    {
+        ComponentView {
+            name: self.name,
+            value: self.debug.map(|debug| debug(world, entity)),
+        }
+    }
+}
+
+impl Display for ComponentView
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+    {
+        match &self.value
+        {
+            Some(value) => write!(f, "{}: {}", self.name, value),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+fn contains<T: 'static>(world: &World, entity: Entity) -> bool
+{
+    world
+        .storage::<T>()
+        .is_some_and(|storage| storage.contains(entity))
+}
+
+fn count<T: 'static>(world: &World) -> usize
+{
+    world
+        .storage::<T>()
+        .map_or(0, |storage| storage.candidate().count())
+}
+
+// A component borrowed mutably elsewhere is shown as such instead of panicking.
+fn debug<T: Debug + 'static>(world: &World, entity: Entity) -> String
+{
+    match world.try_get::<T>(entity)
+    {
+        Ok(component) => format!("{:?}", *component),
+        Err(error) => format!("<{error}>"),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::hierarchy::Parent;
+    use crate::storage::{Storage, StorageType};
+
+    #[derive(Debug)]
+    struct Health(i32);
+
+    struct Opaque;
+
+    #[derive(Debug)]
+    struct Velocity
+    {
+        x: f32,
+    }
+
+    fn world() -> World
+    {
+        World::new()
+            .debuggable::<Health>()
+            .register_with::<Velocity>(StorageType::Table)
+            .debuggable::<Velocity>()
+            .register::<Opaque>()
+    }
+
+    #[test]
+    fn inspect_lists_components_by_name()
+    {
+        let mut world = world();
+        let entity = world
+            .spawn()
+            .with(Health(3))
+            .with(Velocity { x: 1.5 })
+            .with(Opaque)
+            .into_id();
+
+        let views = world.inspect(entity).unwrap();
+        let lines: Vec<_> = views.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                format!("{}: Health(3)", type_name::<Health>()),
+                type_name::<Opaque>().to_string(),
+                format!("{}: Velocity {{ x: 1.5 }}", type_name::<Velocity>()),
+            ]
+        );
+
+        world.for_each_mut::<Velocity>(|_, mut velocity| velocity.x += 1.0);
+
+        assert_eq!(
+            world.inspect(entity).unwrap()[2].value.as_deref(),
+            Some("Velocity { x: 2.5 }")
+        );
+    }
+
+    #[test]
+    fn debuggable_does_not_choose_the_storage()
+    {
+        let mut world = World::new()
+            .debuggable::<Velocity>()
+            .register_with::<Velocity>(StorageType::Table);
+        let entity = world.spawn().with(Velocity { x: 1.0 }).into_id();
+
+        assert!(matches!(
+            world.storage::<Velocity>(),
+            Some(Storage::Table(..))
+        ));
+        assert_eq!(
+            world.inspect(entity).unwrap()[0].value.as_deref(),
+            Some("Velocity { x: 1.0 }")
+        );
+    }
+
+    #[test]
+    fn inspect_dead_and_borrowed_entities()
+    {
+        let mut world = world();
+        let entity = world.spawn().with(Health(3)).into_id();
+        let dead = world.spawn().into_id();
+        world.despawn(dead);
+
+        assert!(world.inspect(dead).is_none());
+
+        let mut health = world.get_mut::<Health>(entity).unwrap();
+        health.0 -= 1;
+
+        let views = world.inspect(entity).unwrap();
+
+        assert_eq!(
+            views[0].value.as_deref(),
+            Some(format!("<`{}` is already borrowed.>", type_name::<Health>()).as_str())
+        );
+    }
+
+    #[test]
+    fn count_entities_per_component()
+    {
+        let mut world = world();
+        let parent = world.spawn().with(Health(1)).into_id();
+        world.spawn().with(Health(2)).with_parent(parent);
+        world.spawn().with(Velocity { x: 0.0 });
+
+        let counts = world.component_counts();
+        let count = |name: &str| {
+            counts
+                .iter()
+                .find(|count| count.name == name)
+                .map(|count| count.count)
+        };
+
+        assert_eq!(count(type_name::<Health>()), Some(2));
+        assert_eq!(count(type_name::<Velocity>()), Some(1));
+        assert_eq!(count(type_name::<Parent>()), Some(1));
+        assert_eq!(count(type_name::<Opaque>()), Some(0));
+    }
+}
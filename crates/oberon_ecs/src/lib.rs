@@ -1,5 +1,57 @@
-mod entity;
+mod allocator;
 mod sparse_set;
+mod table;
+
+mod cell;
+pub use cell::{Ref, RefMut};
+
+mod change;
+pub use change::{Added, Changed, Mut, RemovedComponents, Ticks};
+
+mod command;
+pub use command::{Commands, EntityCommands};
+
+mod entity;
+pub use entity::{Entity, EntityBuilder};
+
+mod error;
+pub use error::EcsError;
+
+mod event;
+pub use event::{EventCursor, EventReader, EventWriter, Events};
+
+mod hierarchy;
+pub use hierarchy::{Children, Parent};
+
+mod inspect;
+pub use inspect::{ComponentCount, ComponentView};
+
+mod pool;
+
+mod query;
+pub use query::{Access, Query, QueryData, QueryFilter, QueryIter, TryQueryIter, With, Without};
+
+mod resource;
+pub use resource::{Res, ResMut};
+
+mod schedule;
+pub use schedule::{Executor, IntoSystemConfig, Schedule, Stage, SystemConfig};
+
+#[cfg(feature = "serde")]
+mod snapshot;
+#[cfg(feature = "serde")]
+pub use snapshot::{
+    ComponentValue, EntityMap, EntitySnapshot, MapEntities, Snapshot, SnapshotError, SnapshotFormat,
+};
+
+mod storage;
+pub use storage::StorageType;
+
+mod system;
+pub use system::{
+    ExclusiveSystem, FunctionSystem, IntoSystem, System, SystemParam, SystemParamFunction,
+    SystemParamItem,
+};
 
 mod world;
 pub use world::World;
@@ -0,0 +1,189 @@
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Threads kept alive between the runs of the multi-threaded stages.
+pub(crate) struct ThreadPool
+{
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+// Counts the helpers which have not finished the task yet.
+#[derive(Default)]
+struct Latch
+{
+    state: Mutex<LatchState>,
+    done: Condvar,
+}
+
+#[derive(Default)]
+struct LatchState
+{
+    remaining: usize,
+    panicked: bool,
+}
+
+impl ThreadPool
+{
+    pub fn new(workers: usize) -> Self
+    {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        Self {
+            sender: Some(sender),
+            workers: (0..workers)
+                .map(|_| {
+                    let receiver = receiver.clone();
+                    thread::spawn(move || work(&receiver))
+                })
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.workers.len()
+    }
+
+    /// Runs the `task` on up to `helpers` workers and on the calling thread at the same time,
+    /// returns once all of them are done. A panic of any of them is resumed on the calling thread.
+    pub fn broadcast(&self, helpers: usize, task: &(dyn Fn() + Sync))
+    {
+        let helpers = helpers.min(self.len());
+        let latch = Arc::new(Latch::default());
+        latch.state.lock().unwrap().remaining = helpers;
+
+        // SAFETY: the task is borrowed only until this function returns, which waits for every
+        // helper to finish running it, even when the task panics.
+        let task: &'static (dyn Fn() + Sync) = unsafe { mem::transmute(task) };
+        let sender = self
+            .sender
+            .as_ref()
+            .expect("The pool is running until dropped.");
+
+        for _ in 0..helpers
+        {
+            let latch = latch.clone();
+
+            sender
+                .send(Box::new(move || {
+                    let panicked = panic::catch_unwind(AssertUnwindSafe(task)).is_err();
+                    latch.count_down(panicked);
+                }))
+                .expect("The workers live as long as the pool.");
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(task));
+        let helper_panicked = latch.wait();
+
+        if let Err(payload) = result
+        {
+            panic::resume_unwind(payload);
+        }
+        if helper_panicked
+        {
+            panic!("A system panicked on a worker thread.");
+        }
+    }
+}
+
+impl Drop for ThreadPool
+{
+    fn drop(&mut self)
+    {
+        // Closing the channel stops the workers once they finish their jobs.
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..)
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Latch
+{
+    fn count_down(&self, panicked: bool)
+    {
+        let mut state = self.state.lock().unwrap();
+
+        state.remaining -= 1;
+        state.panicked |= panicked;
+
+        if state.remaining == 0
+        {
+            self.done.notify_all();
+        }
+    }
+
+    // Returns whether any of the helpers panicked.
+    fn wait(&self) -> bool
+    {
+        let state = self
+            .done
+            .wait_while(self.state.lock().unwrap(), |state| state.remaining > 0)
+            .unwrap();
+
+        state.panicked
+    }
+}
+
+fn work(receiver: &Mutex<Receiver<Job>>)
+{
+    loop
+    {
+        let job = receiver.lock().unwrap().recv();
+
+        match job
+        {
+            Ok(job) => job(),
+            Err(_) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn broadcast_runs_on_the_workers_and_the_caller()
+    {
+        let pool = ThreadPool::new(3);
+        let runs = AtomicUsize::new(0);
+
+        pool.broadcast(2, &|| {
+            runs.fetch_add(1, Ordering::Relaxed);
+        });
+        pool.broadcast(10, &|| {
+            runs.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(runs.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "A system panicked on a worker thread.")]
+    fn resume_panics_of_the_workers()
+    {
+        let pool = ThreadPool::new(1);
+        let caller = thread::current().id();
+
+        pool.broadcast(1, &|| {
+            if thread::current().id() != caller
+            {
+                panic!("worker");
+            }
+        });
+    }
+}
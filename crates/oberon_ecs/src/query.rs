@@ -0,0 +1,969 @@
+use std::any::{type_name, TypeId};
+use std::marker::PhantomData;
+
+use crate::allocator::EntityAllocator;
+use crate::cell::Ref;
+use crate::change::{Mut, Ticks};
+use crate::entity::Entity;
+use crate::error::EcsError;
+use crate::sparse_set::ComponentStorage;
+use crate::storage::{
+    ArchetypeStorage, BorrowedRows, Candidate, Storage, StorageBorrow, StorageMut, StorageRef,
+};
+use crate::table::{Archetype, ColumnMut, ColumnRef, TableColumn};
+use crate::world::World;
+
+/// Components borrowed by a query, used to reject the queries which would borrow the same
+/// component mutably more than once and to find the systems which can run in parallel.
+#[derive(Debug, Default)]
+pub struct Access
+{
+    world: bool,
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+    resource_reads: Vec<(TypeId, &'static str)>,
+    resource_writes: Vec<(TypeId, &'static str)>,
+}
+
+/// Data fetched for every matching entity, implemented for `Entity`, `&T`, `&mut T`, `Option<D>`
+/// and tuples of those. The fetch borrows the table columns for as long as it lives, so the items
+/// borrow the fetch.
+pub trait QueryData
+{
+    type Fetch<'w>;
+    type Item<'q>;
+    /// Fetch narrowed to the entities of a single archetype.
+    type Rows<'q>;
+
+    fn access(access: &mut Access);
+
+    /// Returns `None` if any of the required components was never registered.
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>;
+
+    /// Offers the storages of the required components, the smallest one drives the iteration.
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>);
+
+    /// Returns `None` if the entity does not match, fails if a component is already borrowed.
+    fn fetch<'q>(
+        fetch: &'q Self::Fetch<'_>, entity: Entity,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>;
+
+    /// Looks up the table columns once for all of the archetype entities. Returns `None` if none
+    /// of them can match.
+    fn rows<'q>(fetch: &'q Self::Fetch<'_>, archetype: &'q Archetype) -> Option<Self::Rows<'q>>;
+
+    /// Like `fetch`, for the entity stored at the `row` of the archetype.
+    fn fetch_row<'q>(
+        rows: &Self::Rows<'q>, entity: Entity, row: usize,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>;
+}
+
+/// Condition checked for every entity without borrowing any of its components.
+pub trait QueryFilter
+{
+    type Fetch<'w>;
+    type Rows<'w>;
+
+    /// Returns `None` if no entity can ever match the filter.
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>;
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>);
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool;
+
+    /// Returns `None` if no entity of the archetype can match the filter.
+    fn rows<'w>(fetch: &Self::Fetch<'w>, archetype: &'w Archetype) -> Option<Self::Rows<'w>>;
+
+    fn matches_row(rows: &Self::Rows<'_>, entity: Entity, row: usize) -> bool;
+}
+
+/// Matches the entities having the `T` component, without borrowing it.
+pub struct With<T>(PhantomData<T>);
+
+/// Matches the entities which do not have the `T` component.
+pub struct Without<T>(PhantomData<T>);
+
+/// Entities having all of the components requested by `D` and passing the `F` filter. The sparse
+/// set components are borrowed one by one like `World::get` and `World::get_mut` do, the table
+/// columns are borrowed as a whole for as long as the query lives. The items cannot outlive the
+/// query and the query cannot be created if it would borrow a component mutably twice.
+pub struct Query<'w, D: QueryData, F: QueryFilter = ()>
+{
+    entities: &'w EntityAllocator,
+    state: Option<(D::Fetch<'w>, F::Fetch<'w>)>,
+    driver: Driver<'w>,
+}
+
+pub struct QueryIter<'q, 'w, D: QueryData, F: QueryFilter>
+{
+    query: &'q Query<'w, D, F>,
+    archetype: usize,
+    cursor: usize,
+    rows: Option<Rows<'q, 'w, D, F>>,
+}
+
+pub struct TryQueryIter<'q, 'w, D: QueryData, F: QueryFilter>
+{
+    iter: QueryIter<'q, 'w, D, F>,
+}
+
+// Entities of the current archetype with the columns of the query.
+type Rows<'q, 'w, D, F> = (
+    &'q [Entity],
+    <D as QueryData>::Rows<'q>,
+    <F as QueryFilter>::Rows<'w>,
+);
+
+enum Driver<'w>
+{
+    Entities(&'w EntityAllocator),
+    Sparse(&'w dyn ComponentStorage),
+    Table(Vec<&'w Archetype>),
+}
+
+impl Access
+{
+    pub fn read<T: 'static>(&mut self)
+    {
+        self.reads.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    pub fn write<T: 'static>(&mut self)
+    {
+        self.writes.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    pub fn read_resource<T: 'static>(&mut self)
+    {
+        self.resource_reads
+            .push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    pub fn write_resource<T: 'static>(&mut self)
+    {
+        self.resource_writes
+            .push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    /// Marks every component and resource as read.
+    pub fn read_world(&mut self)
+    {
+        self.world = true;
+    }
+
+    /// Returns `true` if neither of the accesses writes anything the other one borrows.
+    pub fn is_compatible(&self, other: &Access) -> bool
+    {
+        let writes =
+            |access: &Access| !access.writes.is_empty() || !access.resource_writes.is_empty();
+
+        if self.world || other.world
+        {
+            return !(self.world && writes(other) || other.world && writes(self));
+        }
+
+        disjoint(&self.writes, &other.reads)
+            && disjoint(&self.writes, &other.writes)
+            && disjoint(&self.reads, &other.writes)
+            && disjoint(&self.resource_writes, &other.resource_reads)
+            && disjoint(&self.resource_writes, &other.resource_writes)
+            && disjoint(&self.resource_reads, &other.resource_writes)
+    }
+
+    /// Name of the first component or resource which is written and also read or written
+    /// somewhere else.
+    pub fn conflict(&self) -> Option<&'static str>
+    {
+        conflict(&self.reads, &self.writes)
+            .or_else(|| conflict(&self.resource_reads, &self.resource_writes))
+    }
+}
+
+impl<'w, D: QueryData, F: QueryFilter> Query<'w, D, F>
+{
+    pub(crate) fn new(world: &'w World, ticks: Ticks) -> Self
+    {
+        Self::try_new(world, ticks).unwrap_or_else(|error| {
+            panic!("Query `{}` cannot be created: {}", type_name::<D>(), error)
+        })
+    }
+
+    /// Fails if the query would borrow a component mutably more than once.
+    pub(crate) fn try_new(world: &'w World, ticks: Ticks) -> Result<Self, EcsError>
+    {
+        let mut access = Access::default();
+        D::access(&mut access);
+
+        if let Some(component) = access.conflict()
+        {
+            return Err(EcsError::BorrowConflict(component));
+        }
+
+        let state = D::init(world, ticks).zip(F::init(world, ticks));
+        let mut driver = None;
+
+        if let Some((data, filter)) = &state
+        {
+            D::drive(data, &mut driver);
+            F::drive(filter, &mut driver);
+        }
+
+        let entities = world.entities();
+        let driver = match driver
+        {
+            None => Driver::Entities(entities),
+            Some(Candidate::Sparse(storage)) => Driver::Sparse(storage),
+            Some(candidate @ Candidate::Table(..)) => Driver::Table(candidate.archetypes()),
+        };
+
+        Ok(Self {
+            entities,
+            state,
+            driver,
+        })
+    }
+
+    /// Returns the requested components of a single entity, if it matches the query. Panics if
+    /// any of the components is already borrowed.
+    pub fn get(&self, entity: Entity) -> Option<D::Item<'_>>
+    {
+        self.try_get(entity)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like `get`, but fails instead of panicking. A dead entity is not an error here, it just
+    /// does not match.
+    pub fn try_get(&self, entity: Entity) -> Result<Option<D::Item<'_>>, EcsError>
+    {
+        let Some((data, filter)) = self.state.as_ref()
+        else
+        {
+            return Ok(None);
+        };
+
+        if !self.entities.is_alive(entity) || !F::matches(filter, entity)
+        {
+            return Ok(None);
+        }
+        D::fetch(data, entity)
+    }
+
+    /// Panics if any of the components is already borrowed.
+    #[inline]
+    pub fn iter(&self) -> QueryIter<'_, 'w, D, F>
+    {
+        QueryIter {
+            query: self,
+            archetype: 0,
+            cursor: 0,
+            rows: None,
+        }
+    }
+
+    /// Like `iter`, but yields an error for every entity whose components are already borrowed.
+    #[inline]
+    pub fn try_iter(&self) -> TryQueryIter<'_, 'w, D, F>
+    {
+        TryQueryIter { iter: self.iter() }
+    }
+}
+
+impl<'q, 'w, D: QueryData, F: QueryFilter> IntoIterator for &'q Query<'w, D, F>
+{
+    type IntoIter = QueryIter<'q, 'w, D, F>;
+    type Item = D::Item<'q>;
+
+    fn into_iter(self) -> Self::IntoIter
+    {
+        self.iter()
+    }
+}
+
+impl<'q, D: QueryData, F: QueryFilter> Iterator for QueryIter<'q, '_, D, F>
+{
+    type Item = D::Item<'q>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.try_next()
+            .map(|item| item.unwrap_or_else(|error| panic!("{error}")))
+    }
+}
+
+impl<'q, D: QueryData, F: QueryFilter> Iterator for TryQueryIter<'q, '_, D, F>
+{
+    type Item = Result<D::Item<'q>, EcsError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.iter.try_next()
+    }
+}
+
+impl<'q, D: QueryData, F: QueryFilter> QueryIter<'q, '_, D, F>
+{
+    fn try_next(&mut self) -> Option<Result<D::Item<'q>, EcsError>>
+    {
+        let query = self.query;
+        let (data, filter) = query.state.as_ref()?;
+
+        let Driver::Table(archetypes) = &query.driver
+        else
+        {
+            loop
+            {
+                let entity = self.next_entity()?;
+
+                if let Some(item) = query.try_get(entity).transpose()
+                {
+                    return Some(item);
+                }
+            }
+        };
+
+        loop
+        {
+            match &self.rows
+            {
+                Some((entities, data_rows, filter_rows)) if self.cursor < entities.len() =>
+                {
+                    let row = self.cursor;
+                    let entity = entities[row];
+                    self.cursor += 1;
+
+                    if !F::matches_row(filter_rows, entity, row)
+                    {
+                        continue;
+                    }
+                    if let Some(item) = D::fetch_row(data_rows, entity, row).transpose()
+                    {
+                        return Some(item);
+                    }
+                }
+                _ =>
+                {
+                    let archetype = *archetypes.get(self.archetype)?;
+
+                    self.archetype += 1;
+                    self.cursor = 0;
+                    self.rows = D::rows(data, archetype)
+                        .zip(F::rows(filter, archetype))
+                        .map(|(data, filter)| (archetype.entities(), data, filter));
+                }
+            }
+        }
+    }
+
+    // Next entity offered by a sparse set or the entity allocator, it does not have to match the
+    // query. The tables are iterated by rows instead.
+    fn next_entity(&mut self) -> Option<Entity>
+    {
+        loop
+        {
+            let entity = match &self.query.driver
+            {
+                Driver::Entities(entities) if self.cursor < entities.capacity() =>
+                {
+                    entities.get(self.cursor)
+                }
+                Driver::Sparse(storage) if self.cursor < storage.count() =>
+                {
+                    Some(storage.entity_at(self.cursor))
+                }
+                _ => return None,
+            };
+            self.cursor += 1;
+
+            if entity.is_some()
+            {
+                return entity;
+            }
+        }
+    }
+}
+
+impl Driver<'_>
+{
+    /// Number of the entities the iteration goes through.
+    #[cfg(test)]
+    fn len(&self) -> usize
+    {
+        match self
+        {
+            Self::Entities(entities) => entities.capacity(),
+            Self::Sparse(storage) => storage.count(),
+            Self::Table(archetypes) => archetypes.iter().map(|a| a.entities().len()).sum(),
+        }
+    }
+}
+
+fn conflict(
+    reads: &[(TypeId, &'static str)], writes: &[(TypeId, &'static str)],
+) -> Option<&'static str>
+{
+    writes
+        .iter()
+        .enumerate()
+        .find(|(index, (type_id, _))| {
+            reads.iter().any(|(read, _)| read == type_id)
+                || writes[index + 1..]
+                    .iter()
+                    .any(|(write, _)| write == type_id)
+        })
+        .map(|(_, (_, name))| *name)
+}
+
+fn disjoint(first: &[(TypeId, &'static str)], second: &[(TypeId, &'static str)]) -> bool
+{
+    first
+        .iter()
+        .all(|(type_id, _)| second.iter().all(|(other, _)| other != type_id))
+}
+
+fn conflict_of<T>() -> EcsError
+{
+    EcsError::BorrowConflict(type_name::<T>())
+}
+
+impl QueryData for Entity
+{
+    type Fetch<'w> = ();
+    type Item<'q> = Entity;
+    type Rows<'q> = ();
+
+    fn access(_: &mut Access) {}
+
+    fn init(_: &World, _: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        Some(())
+    }
+
+    fn drive<'w>(_: &Self::Fetch<'w>, _: &mut Option<Candidate<'w>>) {}
+
+    fn fetch<'q>(_: &'q Self::Fetch<'_>, entity: Entity)
+        -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        Ok(Some(entity))
+    }
+
+    fn rows<'q>(_: &'q Self::Fetch<'_>, _: &'q Archetype) -> Option<Self::Rows<'q>>
+    {
+        Some(())
+    }
+
+    fn fetch_row<'q>(
+        _: &Self::Rows<'q>, entity: Entity, _: usize,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        Ok(Some(entity))
+    }
+}
+
+impl<T: 'static> QueryData for &T
+{
+    type Fetch<'w> = StorageRef<'w, T>;
+    type Item<'q> = Ref<'q, T>;
+    type Rows<'q> = BorrowedRows<'q, T, ColumnRef<'q, T>>;
+
+    fn access(access: &mut Access)
+    {
+        access.read::<T>();
+    }
+
+    fn init(world: &World, _: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        world
+            .storage::<T>()
+            .map(|storage| StorageBorrow::new(storage, TableColumn::try_borrow_column))
+    }
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+    {
+        Candidate::narrow(driver, fetch.storage().candidate());
+    }
+
+    fn fetch<'q>(
+        fetch: &'q Self::Fetch<'_>, entity: Entity,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        fetch
+            .get(entity)
+            .map_or(Ok(None), |(rows, row)| Self::fetch_row(&rows, entity, row))
+    }
+
+    fn rows<'q>(fetch: &'q Self::Fetch<'_>, archetype: &'q Archetype) -> Option<Self::Rows<'q>>
+    {
+        fetch.in_archetype(archetype)
+    }
+
+    fn fetch_row<'q>(
+        rows: &Self::Rows<'q>, entity: Entity, row: usize,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        match rows
+        {
+            BorrowedRows::Sparse(storage) => storage
+                .get(entity)
+                .map(|entry| entry.item.try_borrow().ok_or_else(conflict_of::<T>))
+                .transpose(),
+            BorrowedRows::Table(Some(column)) => Ok(Some(Ref::new(column.get(row), None))),
+            BorrowedRows::Table(None) => Err(conflict_of::<T>()),
+        }
+    }
+}
+
+impl<T: 'static> QueryData for &mut T
+{
+    type Fetch<'w> = (StorageMut<'w, T>, u64);
+    type Item<'q> = Mut<'q, T>;
+    type Rows<'q> = (BorrowedRows<'q, T, ColumnMut<'q, T>>, u64);
+
+    fn access(access: &mut Access)
+    {
+        access.write::<T>();
+    }
+
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        world.storage::<T>().map(|storage| {
+            let storage = StorageBorrow::new(storage, TableColumn::try_borrow_column_mut);
+            (storage, ticks.this_run)
+        })
+    }
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+    {
+        Candidate::narrow(driver, fetch.0.storage().candidate());
+    }
+
+    fn fetch<'q>(
+        (storage, tick): &'q Self::Fetch<'_>, entity: Entity,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        storage.get(entity).map_or(Ok(None), |(rows, row)| {
+            Self::fetch_row(&(rows, *tick), entity, row)
+        })
+    }
+
+    fn rows<'q>(
+        (storage, tick): &'q Self::Fetch<'_>, archetype: &'q Archetype,
+    ) -> Option<Self::Rows<'q>>
+    {
+        storage.in_archetype(archetype).map(|rows| (rows, *tick))
+    }
+
+    fn fetch_row<'q>(
+        (rows, tick): &Self::Rows<'q>, entity: Entity, row: usize,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        match rows
+        {
+            BorrowedRows::Sparse(storage) => storage
+                .get(entity)
+                .map(|entry| {
+                    let item = entry.item.try_borrow_mut().ok_or_else(conflict_of::<T>)?;
+                    Ok(Mut::new(item, &entry.changed, *tick))
+                })
+                .transpose(),
+            BorrowedRows::Table(Some(column)) => column
+                .get(row)
+                .map(|item| Some(Mut::new(item, column.changed(row), *tick)))
+                .ok_or_else(conflict_of::<T>),
+            BorrowedRows::Table(None) => Err(conflict_of::<T>()),
+        }
+    }
+}
+
+impl<D: QueryData> QueryData for Option<D>
+{
+    type Fetch<'w> = Option<D::Fetch<'w>>;
+    type Item<'q> = Option<D::Item<'q>>;
+    type Rows<'q> = Option<D::Rows<'q>>;
+
+    fn access(access: &mut Access)
+    {
+        D::access(access);
+    }
+
+    fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        Some(D::init(world, ticks))
+    }
+
+    fn drive<'w>(_: &Self::Fetch<'w>, _: &mut Option<Candidate<'w>>) {}
+
+    fn fetch<'q>(
+        fetch: &'q Self::Fetch<'_>, entity: Entity,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        fetch
+            .as_ref()
+            .map_or(Ok(None), |fetch| D::fetch(fetch, entity))
+            .map(Some)
+    }
+
+    fn rows<'q>(fetch: &'q Self::Fetch<'_>, archetype: &'q Archetype) -> Option<Self::Rows<'q>>
+    {
+        Some(fetch.as_ref().and_then(|fetch| D::rows(fetch, archetype)))
+    }
+
+    fn fetch_row<'q>(
+        rows: &Self::Rows<'q>, entity: Entity, row: usize,
+    ) -> Result<Option<Self::Item<'q>>, EcsError>
+    {
+        rows.as_ref()
+            .map_or(Ok(None), |rows| D::fetch_row(rows, entity, row))
+            .map(Some)
+    }
+}
+
+impl<T: 'static> QueryFilter for With<T>
+{
+    type Fetch<'w> = Storage<'w, T>;
+    type Rows<'w> = ArchetypeStorage<'w, T>;
+
+    fn init(world: &World, _: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        world.storage::<T>()
+    }
+
+    fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+    {
+        Candidate::narrow(driver, fetch.candidate());
+    }
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool
+    {
+        fetch.contains(entity)
+    }
+
+    fn rows<'w>(fetch: &Self::Fetch<'w>, archetype: &'w Archetype) -> Option<Self::Rows<'w>>
+    {
+        fetch.in_archetype(archetype)
+    }
+
+    fn matches_row(rows: &Self::Rows<'_>, entity: Entity, _: usize) -> bool
+    {
+        rows.contains(entity)
+    }
+}
+
+impl<T: 'static> QueryFilter for Without<T>
+{
+    type Fetch<'w> = Option<Storage<'w, T>>;
+    type Rows<'w> = Option<ArchetypeStorage<'w, T>>;
+
+    fn init(world: &World, _: Ticks) -> Option<Self::Fetch<'_>>
+    {
+        Some(world.storage::<T>())
+    }
+
+    fn drive<'w>(_: &Self::Fetch<'w>, _: &mut Option<Candidate<'w>>) {}
+
+    fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool
+    {
+        fetch.is_none_or(|storage| !storage.contains(entity))
+    }
+
+    fn rows<'w>(fetch: &Self::Fetch<'w>, archetype: &'w Archetype) -> Option<Self::Rows<'w>>
+    {
+        match fetch.and_then(|storage| storage.in_archetype(archetype))
+        {
+            Some(ArchetypeStorage::Table(_)) => None,
+            rows => Some(rows),
+        }
+    }
+
+    fn matches_row(rows: &Self::Rows<'_>, entity: Entity, _: usize) -> bool
+    {
+        rows.is_none_or(|rows| !rows.contains(entity))
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables)]
+        impl<$($name: QueryData),*> QueryData for ($($name,)*)
+        {
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+            type Item<'q> = ($($name::Item<'q>,)*);
+            type Rows<'q> = ($($name::Rows<'q>,)*);
+
+            fn access(access: &mut Access)
+            {
+                $($name::access(access);)*
+            }
+
+            fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+            {
+                Some(($($name::init(world, ticks)?,)*))
+            }
+
+            fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+            {
+                let ($($name,)*) = fetch;
+                $($name::drive($name, driver);)*
+            }
+
+            fn fetch<'q>(fetch: &'q Self::Fetch<'_>, entity: Entity)
+                -> Result<Option<Self::Item<'q>>, EcsError>
+            {
+                let ($($name,)*) = fetch;
+                Ok(Some(($(
+                    match $name::fetch($name, entity)?
+                    {
+                        Some(item) => item,
+                        None => return Ok(None),
+                    },
+                )*)))
+            }
+
+            fn rows<'q>(fetch: &'q Self::Fetch<'_>, archetype: &'q Archetype)
+                -> Option<Self::Rows<'q>>
+            {
+                let ($($name,)*) = fetch;
+                Some(($($name::rows($name, archetype)?,)*))
+            }
+
+            fn fetch_row<'q>(rows: &Self::Rows<'q>, entity: Entity, row: usize)
+                -> Result<Option<Self::Item<'q>>, EcsError>
+            {
+                let ($($name,)*) = rows;
+                Ok(Some(($(
+                    match $name::fetch_row($name, entity, row)?
+                    {
+                        Some(item) => item,
+                        None => return Ok(None),
+                    },
+                )*)))
+            }
+        }
+
+        #[allow(non_snake_case, unused_variables)]
+        impl<$($name: QueryFilter),*> QueryFilter for ($($name,)*)
+        {
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+            type Rows<'w> = ($($name::Rows<'w>,)*);
+
+            fn init(world: &World, ticks: Ticks) -> Option<Self::Fetch<'_>>
+            {
+                Some(($($name::init(world, ticks)?,)*))
+            }
+
+            fn drive<'w>(fetch: &Self::Fetch<'w>, driver: &mut Option<Candidate<'w>>)
+            {
+                let ($($name,)*) = fetch;
+                $($name::drive($name, driver);)*
+            }
+
+            fn matches(fetch: &Self::Fetch<'_>, entity: Entity) -> bool
+            {
+                let ($($name,)*) = fetch;
+                true $(&& $name::matches($name, entity))*
+            }
+
+            fn rows<'w>(fetch: &Self::Fetch<'w>, archetype: &'w Archetype)
+                -> Option<Self::Rows<'w>>
+            {
+                let ($($name,)*) = fetch;
+                Some(($($name::rows($name, archetype)?,)*))
+            }
+
+            fn matches_row(rows: &Self::Rows<'_>, entity: Entity, row: usize) -> bool
+            {
+                let ($($name,)*) = rows;
+                true $(&& $name::matches_row($name, entity, row))*
+            }
+        }
+    };
+}
+
+impl_query_tuple!();
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, G);
+impl_query_tuple!(A, B, C, D, E, G, H);
+impl_query_tuple!(A, B, C, D, E, G, H, I);
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::storage::StorageType;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    struct Frozen;
+
+    fn world() -> World
+    {
+        world_with(StorageType::SparseSet)
+    }
+
+    fn world_with(storage_type: StorageType) -> World
+    {
+        let mut world = World::new()
+            .register_with::<Position>(storage_type)
+            .register_with::<Velocity>(storage_type)
+            .register::<Frozen>();
+
+        world.spawn().with(Position(0)).with(Velocity(1));
+        world
+            .spawn()
+            .with(Position(10))
+            .with(Velocity(2))
+            .with(Frozen);
+        world.spawn().with(Position(20));
+        world
+    }
+
+    #[test]
+    fn iterate_entities_having_all_components()
+    {
+        let world = world();
+        let query = world.query::<(&mut Position, &Velocity)>();
+
+        for (mut position, velocity) in &query
+        {
+            position.0 += velocity.0;
+        }
+
+        let positions: Vec<_> = world.query::<&Position>().iter().map(|p| p.0).collect();
+
+        assert_eq!(positions, vec![1, 12, 20]);
+    }
+
+    #[test]
+    fn iterate_table_rows()
+    {
+        let world = world_with(StorageType::Table);
+
+        for (mut position, velocity) in
+            &world.query_filtered::<(&mut Position, &Velocity), Without<Frozen>>()
+        {
+            position.0 += velocity.0;
+        }
+
+        let mut positions: Vec<_> = world.query::<&Position>().iter().map(|p| p.0).collect();
+        positions.sort();
+
+        let frozen = world.query_filtered::<(&Position, Option<&Velocity>), With<Frozen>>();
+        let frozen: Vec<_> = frozen
+            .iter()
+            .map(|(position, velocity)| (position.0, velocity.map(|v| v.0)))
+            .collect();
+
+        assert_eq!(positions, vec![1, 10, 20]);
+        assert_eq!(frozen, vec![(10, Some(2))]);
+    }
+
+    #[test]
+    fn table_columns_are_borrowed_by_the_query()
+    {
+        let world = world_with(StorageType::Table);
+        let entity = world.query::<Entity>().iter().next().unwrap();
+
+        let query = world.query::<&mut Position>();
+        let first = query.try_get(entity).unwrap();
+
+        assert!(first.is_some());
+        assert_eq!(
+            query.try_get(entity).err(),
+            Some(EcsError::BorrowConflict(type_name::<Position>()))
+        );
+        assert!(world.try_get::<Position>(entity).is_err());
+
+        let readers = world.query::<&Position>();
+        assert!(readers.try_iter().all(|item| item.is_err()));
+
+        drop(first);
+        assert!(query.try_get(entity).unwrap().is_some());
+
+        drop((readers, query));
+        assert_eq!(world.query::<&Position>().iter().count(), 3);
+    }
+
+    #[test]
+    fn filter_out_entities_with_component()
+    {
+        let world = world();
+        let query = world.query_filtered::<&Position, (With<Velocity>, Without<Frozen>)>();
+
+        let positions: Vec<_> = query.iter().map(|p| p.0).collect();
+
+        assert_eq!(positions, vec![0]);
+    }
+
+    #[test]
+    fn optional_component_does_not_filter()
+    {
+        let world = world();
+        let query = world.query::<(Entity, Option<&Velocity>)>();
+
+        let velocities: Vec<_> = query
+            .iter()
+            .map(|(_, velocity)| velocity.map(|v| v.0))
+            .collect();
+
+        assert_eq!(velocities, vec![Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn iteration_is_driven_by_smallest_storage()
+    {
+        let world = world();
+        let query = world.query::<(&Position, &Frozen)>();
+
+        assert_eq!(query.driver.len(), 1);
+        assert_eq!(query.iter().count(), 1);
+    }
+
+    #[test]
+    fn query_with_unregistered_component_is_empty()
+    {
+        let world = world();
+        let query = world.query::<(&Position, &String)>();
+
+        assert_eq!(query.iter().count(), 0);
+    }
+
+    #[test]
+    fn get_single_entity()
+    {
+        let mut world = world();
+        let entity = world.spawn().with(Position(5)).into_id();
+
+        let query = world.query_filtered::<&Position, Without<Velocity>>();
+
+        assert_eq!(*query.get(entity).unwrap(), Position(5));
+    }
+
+    #[test]
+    fn skip_despawned_entities()
+    {
+        let mut world = world();
+        let entity = world.spawn().with(Position(5)).into_id();
+        world.despawn(entity);
+
+        let query = world.query::<Entity>();
+
+        assert_eq!(query.iter().count(), 3);
+        assert!(query.get(entity).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_when_component_is_borrowed_mutably_twice()
+    {
+        let world = world();
+        world.query::<(&mut Position, Option<&Position>)>();
+    }
+}
@@ -0,0 +1,224 @@
+use std::any::type_name;
+use std::ops::{Deref, DerefMut};
+
+use crate::cell::{Ref, RefMut};
+use crate::change::Ticks;
+use crate::query::Access;
+use crate::system::SystemParam;
+use crate::world::World;
+
+/// Shared borrow of a `T` resource, the system panics if the resource does not exist. Take
+/// `Option<Res<T>>` for resources which might be missing.
+pub struct Res<'w, T: 'static>
+{
+    value: Ref<'w, T>,
+}
+
+/// Mutable borrow of a `T` resource, the system panics if the resource does not exist. Take
+/// `Option<ResMut<T>>` for resources which might be missing.
+pub struct ResMut<'w, T: 'static>
+{
+    value: RefMut<'w, T>,
+}
+
+impl<T> Deref for Res<'_, T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        &self.value
+    }
+}
+
+impl<T> Deref for ResMut<'_, T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target
+    {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for ResMut<'_, T>
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target
+    {
+        &mut self.value
+    }
+}
+
+impl<T: 'static> SystemParam for Res<'_, T>
+{
+    type Item<'w, 's> = Res<'w, T>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.read_resource::<T>();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        let value = world.resource::<T>().unwrap_or_else(|| missing::<T>());
+        Res { value }
+    }
+}
+
+impl<T: 'static> SystemParam for ResMut<'_, T>
+{
+    type Item<'w, 's> = ResMut<'w, T>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.write_resource::<T>();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        let value = world.resource_mut::<T>().unwrap_or_else(|| missing::<T>());
+        ResMut { value }
+    }
+}
+
+impl<T: 'static> SystemParam for Option<Res<'_, T>>
+{
+    type Item<'w, 's> = Option<Res<'w, T>>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.read_resource::<T>();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        world.resource::<T>().map(|value| Res { value })
+    }
+}
+
+impl<T: 'static> SystemParam for Option<ResMut<'_, T>>
+{
+    type Item<'w, 's> = Option<ResMut<'w, T>>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.write_resource::<T>();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        world.resource_mut::<T>().map(|value| ResMut { value })
+    }
+}
+
+fn missing<T>() -> !
+{
+    panic!("Resource `{}` does not exist.", type_name::<T>());
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::query::Query;
+    use crate::schedule::{IntoSystemConfig, Schedule, Stage};
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+
+    struct Points(u32);
+
+    #[test]
+    fn insert_and_borrow_resource()
+    {
+        let mut world = World::new();
+
+        assert!(world.insert_resource(Score(1)).is_none());
+        assert_eq!(world.insert_resource(Score(2)), Some(Score(1)));
+
+        world.resource_mut::<Score>().unwrap().0 += 1;
+
+        assert_eq!(*world.resource::<Score>().unwrap(), Score(3));
+        assert!(world.resource::<Points>().is_none());
+    }
+
+    #[test]
+    fn remove_resource()
+    {
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+
+        assert_eq!(world.remove_resource::<Score>(), Some(Score(1)));
+        assert!(!world.contains_resource::<Score>());
+    }
+
+    #[test]
+    fn systems_access_resources()
+    {
+        fn collect(mut score: ResMut<Score>, query: Query<&Points>)
+        {
+            score.0 += query.iter().map(|points| points.0).sum::<u32>();
+        }
+
+        fn bonus(score: Option<ResMut<Score>>, missing: Option<Res<Points>>)
+        {
+            if let (Some(mut score), None) = (score, missing)
+            {
+                score.0 *= 2;
+            }
+        }
+
+        let mut world = World::new().register::<Points>();
+        world.insert_resource(Score(0));
+        world.spawn().with(Points(2));
+        world.spawn().with(Points(3));
+
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Update, collect.label("collect"))
+            .add_system(Stage::Update, bonus.after("collect"));
+
+        schedule.run(&mut world);
+
+        assert_eq!(*world.resource::<Score>().unwrap(), Score(10));
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_when_system_borrows_resource_mutably_twice()
+    {
+        fn conflicting(_: ResMut<Score>, _: Res<Score>) {}
+
+        let mut world = World::new();
+        world.insert_resource(Score(0));
+
+        Schedule::new()
+            .add_system(Stage::Update, conflicting)
+            .run(&mut world);
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_when_resource_is_missing()
+    {
+        fn read(_: Res<Score>) {}
+
+        Schedule::new()
+            .add_system(Stage::Update, read)
+            .run(&mut World::new());
+    }
+}
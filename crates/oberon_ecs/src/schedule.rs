@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::thread;
+
+use crate::pool::ThreadPool;
+use crate::system::{IntoSystem, System};
+use crate::world::World;
+
+/// Group of systems running together, the stages run in the order of declaration.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Stage
+{
+    /// Runs only once, before the first frame.
+    Startup,
+    /// Not a part of the frame, run by the application at a fixed rate.
+    FixedUpdate,
+    PreUpdate,
+    Update,
+    PostUpdate,
+    /// Runs last, draws the frame.
+    Render,
+}
+
+/// How the systems of a stage are run.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Executor
+{
+    /// One after another, on the calling thread.
+    #[default]
+    SingleThreaded,
+    /// The systems which do not borrow the same components or resources mutably and have no
+    /// ordering constraint between them run at the same time on a pool of threads, kept by the
+    /// schedule. Every system is put into the batch right after the last one holding an earlier
+    /// system of the stage it conflicts with, and the batches run one after another, so a slow
+    /// system holds back the whole next batch. Exclusive systems always run alone.
+    MultiThreaded,
+}
+
+/// System together with its labels and ordering constraints. The constraints refer to the labels
+/// of other systems of the same stage, running the stage panics when no system in it has the label.
+pub struct SystemConfig
+{
+    system: Box<dyn System>,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    initialized: bool,
+}
+
+pub trait IntoSystemConfig<Marker>: Sized
+{
+    fn into_config(self) -> SystemConfig;
+
+    /// Names the system for the ordering constraints, several systems can share the same label.
+    fn label(self, label: &'static str) -> SystemConfig
+    {
+        let mut config = self.into_config();
+        config.labels.push(label);
+        config
+    }
+
+    /// Runs the system before the systems with the `label`.
+    fn before(self, label: &'static str) -> SystemConfig
+    {
+        let mut config = self.into_config();
+        config.before.push(label);
+        config
+    }
+
+    /// Runs the system after the systems with the `label`.
+    fn after(self, label: &'static str) -> SystemConfig
+    {
+        let mut config = self.into_config();
+        config.after.push(label);
+        config
+    }
+}
+
+#[derive(Default)]
+pub struct Schedule
+{
+    stages: HashMap<Stage, StageSystems>,
+    executor: Executor,
+    /// Workers of the multi-threaded executor, the calling thread runs the systems as well.
+    pool: Option<ThreadPool>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct StageSystems
+{
+    configs: Vec<SystemConfig>,
+    /// Indices of the sorted systems which can run in parallel, the batches run one by one.
+    batches: Vec<Vec<usize>>,
+    /// Whether the systems were sorted, initialized and batched since the last one was added.
+    prepared: bool,
+}
+
+impl<M, S: IntoSystem<M>> IntoSystemConfig<M> for S
+{
+    fn into_config(self) -> SystemConfig
+    {
+        SystemConfig {
+            system: Box::new(self.into_system()),
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            initialized: false,
+        }
+    }
+}
+
+impl IntoSystemConfig<()> for SystemConfig
+{
+    #[inline]
+    fn into_config(self) -> SystemConfig
+    {
+        self
+    }
+}
+
+impl Stage
+{
+    pub const FRAME: [Stage; 4] = [
+        Stage::PreUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::Render,
+    ];
+}
+
+impl Schedule
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// The multi-threaded executor starts a worker for every core of the machine but one.
+    pub fn with_executor(mut self, executor: Executor) -> Self
+    {
+        self.executor = executor;
+        self.pool = match executor
+        {
+            Executor::SingleThreaded => None,
+            Executor::MultiThreaded =>
+            {
+                let cores = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+                Some(ThreadPool::new(cores - 1))
+            }
+        };
+        self
+    }
+
+    pub fn add_system<M>(mut self, stage: Stage, system: impl IntoSystemConfig<M>) -> Self
+    {
+        let systems = self.stages.entry(stage).or_default();
+
+        systems.configs.push(system.into_config());
+        systems.prepared = false;
+        self
+    }
+
+    /// Runs all of the frame stages, the startup one is run before the first frame. The events
+    /// and the change trackers are updated before the frame stages.
+    pub fn run(&mut self, world: &mut World)
+    {
+        self.run_startup(world);
+        world.update_events();
+        world.clear_trackers();
+
+        for stage in Stage::FRAME
+        {
+            self.run_stage(stage, world);
+        }
+    }
+
+    /// Runs the startup stage, if it has not been run yet.
+    pub fn run_startup(&mut self, world: &mut World)
+    {
+        if !self.started
+        {
+            self.started = true;
+            self.run_stage(Stage::Startup, world);
+        }
+    }
+
+    /// Runs the systems of the stage and applies their commands afterwards.
+    pub fn run_stage(&mut self, stage: Stage, world: &mut World)
+    {
+        let Some(systems) = self.stages.get_mut(&stage)
+        else
+        {
+            return;
+        };
+
+        systems.prepare(world);
+
+        match &self.pool
+        {
+            None => systems
+                .configs
+                .iter_mut()
+                .for_each(|config| config.system.run(world)),
+            Some(pool) =>
+            {
+                for batch in &systems.batches
+                {
+                    match batch[..]
+                    {
+                        [index] => systems.configs[index].system.run(world),
+                        _ => run_parallel(pool, &mut systems.configs, batch, world),
+                    }
+                }
+            }
+        }
+
+        systems
+            .configs
+            .iter_mut()
+            .for_each(|config| config.system.apply_deferred(world));
+    }
+}
+
+// Runs the systems of the batch on the workers of the pool and on the calling thread, every thread
+// takes the next system which has not been started yet.
+fn run_parallel(pool: &ThreadPool, configs: &mut [SystemConfig], batch: &[usize], world: &World)
+{
+    let pending = Mutex::new(
+        configs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| batch.contains(index))
+            .map(|(_, config)| config),
+    );
+
+    pool.broadcast(batch.len() - 1, &|| loop
+    {
+        let next = pending.lock().unwrap().next();
+
+        match next
+        {
+            Some(config) => config.system.run_shared(world),
+            None => break,
+        }
+    });
+}
+
+impl StageSystems
+{
+    // Sorting may move the systems added after a run in front of the initialized ones, so every
+    // system remembers whether it was initialized.
+    fn prepare(&mut self, world: &mut World)
+    {
+        if self.prepared
+        {
+            return;
+        }
+
+        self.sort();
+
+        for config in self.configs.iter_mut().filter(|config| !config.initialized)
+        {
+            config.system.initialize(world);
+            config.initialized = true;
+        }
+
+        self.batch();
+        self.prepared = true;
+    }
+
+    // Puts every system into the batch right after the last one holding an earlier system it
+    // conflicts with, so the systems which conflict keep their order.
+    fn batch(&mut self)
+    {
+        let mut batch_of: Vec<usize> = Vec::with_capacity(self.configs.len());
+
+        for index in 0..self.configs.len()
+        {
+            let batch = (0..index)
+                .filter(|&other| !self.can_run_with(index, other))
+                .map(|other| batch_of[other] + 1)
+                .max()
+                .unwrap_or(0);
+
+            batch_of.push(batch);
+        }
+
+        self.batches = vec![Vec::new(); batch_of.iter().max().map_or(0, |last| last + 1)];
+
+        for (index, batch) in batch_of.into_iter().enumerate()
+        {
+            self.batches[batch].push(index);
+        }
+    }
+
+    fn can_run_with(&self, index: usize, other: usize) -> bool
+    {
+        let (config, other) = (&self.configs[index], &self.configs[other]);
+        let constrained = |first: &SystemConfig, second: &SystemConfig| {
+            second
+                .labels
+                .iter()
+                .any(|label| first.before.contains(label) || first.after.contains(label))
+        };
+
+        match (config.system.access(), other.system.access())
+        {
+            (Some(access), Some(other_access)) =>
+            {
+                access.is_compatible(other_access)
+                    && !constrained(config, other)
+                    && !constrained(other, config)
+            }
+            _ => false,
+        }
+    }
+
+    // Topological sort which keeps the insertion order of the systems without constraints.
+    fn sort(&mut self)
+    {
+        let count = self.configs.len();
+        let labelled = |config: &SystemConfig, label: &str| {
+            let indices: Vec<_> = (0..count)
+                .filter(|&index| self.configs[index].labels.contains(&label))
+                .collect();
+
+            if indices.is_empty()
+            {
+                panic!(
+                    "System `{}` is ordered against `{label}`, which is not a label of any system \
+                     in the stage.",
+                    config.system.name()
+                );
+            }
+            indices
+        };
+
+        let mut successors = vec![Vec::new(); count];
+        let mut predecessors = vec![0; count];
+
+        for (index, config) in self.configs.iter().enumerate()
+        {
+            let before = config
+                .before
+                .iter()
+                .flat_map(|label| labelled(config, label));
+            let after = config
+                .after
+                .iter()
+                .flat_map(|label| labelled(config, label));
+
+            for (from, to) in before
+                .map(|other| (index, other))
+                .chain(after.map(|other| (other, index)))
+                .filter(|(from, to)| from != to)
+            {
+                successors[from].push(to);
+                predecessors[to] += 1;
+            }
+        }
+
+        let mut order = Vec::with_capacity(count);
+        let mut visited = vec![false; count];
+
+        while order.len() < count
+        {
+            let Some(next) = (0..count).find(|&index| !visited[index] && predecessors[index] == 0)
+            else
+            {
+                let cycle: Vec<_> = (0..count)
+                    .filter(|&index| !visited[index])
+                    .map(|index| self.configs[index].system.name())
+                    .collect();
+
+                panic!("Ordering of the systems {cycle:?} contains a cycle.");
+            };
+
+            visited[next] = true;
+            order.push(next);
+
+            for &successor in &successors[next]
+            {
+                predecessors[successor] -= 1;
+            }
+        }
+
+        let mut configs: Vec<_> = self.configs.drain(..).map(Some).collect();
+
+        self.configs = order
+            .into_iter()
+            .filter_map(|index| configs[index].take())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::query::{Query, Without};
+    use crate::resource::{Res, ResMut};
+
+    struct Position(i32);
+
+    struct Velocity(i32);
+
+    struct Frozen;
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    fn setup(world: &mut World)
+    {
+        world.spawn().with(Position(0)).with(Velocity(2));
+        world
+            .spawn()
+            .with(Position(0))
+            .with(Velocity(3))
+            .with(Frozen);
+    }
+
+    fn movement(query: Query<(&mut Position, &Velocity), Without<Frozen>>)
+    {
+        for (mut position, velocity) in &query
+        {
+            position.0 += velocity.0;
+        }
+    }
+
+    fn world() -> World
+    {
+        World::new()
+            .register::<Position>()
+            .register::<Velocity>()
+            .register::<Frozen>()
+            .register::<Log>()
+    }
+
+    fn positions(world: &World) -> Vec<i32>
+    {
+        world.query::<&Position>().iter().map(|p| p.0).collect()
+    }
+
+    #[test]
+    fn startup_runs_only_once()
+    {
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Startup, setup)
+            .add_system(Stage::Update, movement);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.len(), 2);
+        assert_eq!(positions(&world), vec![4, 0]);
+    }
+
+    #[test]
+    fn stages_run_in_order()
+    {
+        fn first(world: &mut World)
+        {
+            let log = world.spawn().with(Log::default()).into_id();
+            world.get_mut::<Log>(log).unwrap().0.push("pre");
+        }
+
+        fn second(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("update"));
+        }
+
+        fn third(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("post"));
+        }
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::PostUpdate, third)
+            .add_system(Stage::Update, second)
+            .add_system(Stage::PreUpdate, first);
+
+        schedule.run(&mut world);
+
+        let log = world.query::<&Log>();
+        let log = log.iter().next().unwrap();
+
+        assert_eq!(log.0, vec!["pre", "update", "post"]);
+    }
+
+    #[test]
+    fn ordering_constraints_are_respected()
+    {
+        fn spawn(world: &mut World)
+        {
+            world.spawn().with(Log::default());
+        }
+
+        fn a(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("a"));
+        }
+
+        fn b(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("b"));
+        }
+
+        fn c(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("c"));
+        }
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Startup, spawn)
+            .add_system(Stage::Update, a.label("a").after("c"))
+            .add_system(Stage::Update, b.label("b"))
+            .add_system(Stage::Update, c.label("c").after("b").before("a"));
+
+        schedule.run(&mut world);
+
+        let log = world.query::<&Log>();
+        let log = log.iter().next().unwrap();
+
+        assert_eq!(log.0, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn add_system_after_a_run()
+    {
+        fn spawn(world: &mut World)
+        {
+            world.spawn().with(Log::default());
+        }
+
+        fn a(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("a"));
+        }
+
+        fn b(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("b"));
+        }
+
+        fn c(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("c"));
+        }
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Startup, spawn)
+            .add_system(Stage::Update, a.label("a"))
+            .add_system(Stage::Update, b);
+
+        schedule.run(&mut world);
+
+        let mut schedule = schedule.add_system(Stage::Update, c.before("a"));
+        schedule.run(&mut world);
+
+        let log = world.query::<&Log>();
+        let log = log.iter().next().unwrap();
+
+        assert_eq!(log.0, vec!["a", "b", "b", "c", "a"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_on_ordering_cycle()
+    {
+        fn a(_: Query<&Position>) {}
+
+        fn b(_: Query<&Position>) {}
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Update, a.label("a").after("b"))
+            .add_system(Stage::Update, b.label("b").after("a"));
+
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a label of any system")]
+    fn crash_on_unknown_label()
+    {
+        fn a(_: Query<&Position>) {}
+
+        let mut world = world();
+        let mut schedule = Schedule::new().add_system(Stage::Update, a.after("missing"));
+
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    fn labels_group_systems()
+    {
+        fn spawn(world: &mut World)
+        {
+            world.spawn().with(Log::default());
+        }
+
+        fn first(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("first"));
+        }
+
+        fn second(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("second"));
+        }
+
+        fn last(query: Query<&mut Log>)
+        {
+            query.iter().for_each(|mut log| log.0.push("last"));
+        }
+
+        let mut world = world();
+        let mut schedule = Schedule::new()
+            .add_system(Stage::Startup, spawn)
+            .add_system(Stage::Update, last.after("physics"))
+            .add_system(Stage::Update, first.label("physics"))
+            .add_system(Stage::Update, second.label("physics"));
+
+        schedule.run(&mut world);
+
+        let log = world.query::<&Log>();
+        let log = log.iter().next().unwrap();
+
+        assert_eq!(log.0, vec!["first", "second", "last"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn crash_when_system_borrows_component_mutably_twice()
+    {
+        fn conflicting(_: Query<&mut Position>, _: Query<&Position>) {}
+
+        let mut world = world();
+        let mut schedule = Schedule::new().add_system(Stage::Update, conflicting);
+
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    fn multi_threaded_executor_runs_all_systems()
+    {
+        fn spawn(world: &mut World)
+        {
+            world.spawn().with(Position(0)).with(Velocity(1));
+        }
+
+        fn count(query: Query<&Velocity>, mut log: ResMut<Log>)
+        {
+            log.0.extend(query.iter().map(|_| "velocity"));
+        }
+
+        let mut world = world();
+        world.insert_resource(Log::default());
+
+        let mut schedule = Schedule::new()
+            .with_executor(Executor::MultiThreaded)
+            .add_system(Stage::Startup, setup)
+            .add_system(Stage::Update, movement)
+            .add_system(Stage::Update, count)
+            .add_system(Stage::Update, spawn);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(positions(&world), vec![4, 0, 1, 0]);
+        assert_eq!(world.resource::<Log>().unwrap().0, vec!["velocity"; 5]);
+    }
+
+    #[test]
+    fn batch_systems_without_conflicts()
+    {
+        fn read_position(_: Query<&Position>) {}
+
+        fn read_velocity(_: Query<&Velocity>, _: Res<Log>) {}
+
+        fn write_velocity(_: Query<&mut Velocity>) {}
+
+        fn read_frozen(_: Query<&Frozen>) {}
+
+        fn write_frozen(_: Query<&mut Frozen>) {}
+
+        fn write_log(_: ResMut<Log>) {}
+
+        fn read_world(_: &World) {}
+
+        fn exclusive(_: &mut World) {}
+
+        let mut world = world();
+        let mut systems = StageSystems {
+            configs: vec![
+                movement.into_config(),
+                read_velocity.into_config(),
+                write_velocity.into_config(),
+                read_position.into_config(),
+                exclusive.into_config(),
+                read_world.into_config(),
+                read_frozen.into_config(),
+                write_log.label("log"),
+                write_frozen.after("log"),
+            ],
+            ..Default::default()
+        };
+        systems.prepare(&mut world);
+
+        assert_eq!(
+            systems.batches,
+            vec![
+                vec![0, 1],
+                vec![2, 3],
+                vec![4],
+                vec![5, 6],
+                vec![7],
+                vec![8]
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_systems_across_the_stage()
+    {
+        fn write_position(_: Query<&mut Position>) {}
+
+        fn read_position(_: Query<&Position>) {}
+
+        fn write_velocity(_: Query<&mut Velocity>) {}
+
+        fn read_both(_: Query<(&Position, &Velocity)>) {}
+
+        let mut world = world();
+        let mut systems = StageSystems {
+            configs: vec![
+                write_position.into_config(),
+                read_position.into_config(),
+                write_velocity.into_config(),
+                read_both.into_config(),
+            ],
+            ..Default::default()
+        };
+        systems.prepare(&mut world);
+
+        assert_eq!(systems.batches, vec![vec![0, 2], vec![1, 3]]);
+    }
+}
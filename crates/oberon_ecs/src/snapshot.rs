@@ -0,0 +1,581 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use ron::error::SpannedError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::entity::Entity;
+use crate::hierarchy::{Children, Parent};
+use crate::world::World;
+
+/// Saved entities together with their serializable components, keyed by the names the components
+/// were made serializable with. The other components are not saved.
+#[derive(Debug, Serialize)]
+pub struct Snapshot
+{
+    #[serde(skip)]
+    format: SnapshotFormat,
+    pub entities: Vec<EntitySnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntitySnapshot
+{
+    /// Handle of the entity in the saved world, the loaded entity gets a new one.
+    pub entity: Entity,
+    pub components: BTreeMap<String, ComponentValue>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapshotFormat
+{
+    Json,
+    Ron,
+}
+
+/// Component kept as it was written in the format of the snapshot, so it is deserialized straight
+/// into its type. Unlike JSON, RON keeps the names of the enum variants and structs.
+#[derive(Clone, Debug)]
+pub enum ComponentValue
+{
+    Json(Box<serde_json::value::RawValue>),
+    Ron(Box<ron::value::RawValue>),
+}
+
+// Layout of the snapshot documents, the components are read as raw values of the format.
+#[derive(Deserialize)]
+struct Document<V>
+{
+    entities: Vec<DocumentEntity<V>>,
+}
+
+#[derive(Deserialize)]
+struct DocumentEntity<V>
+{
+    entity: Entity,
+    components: BTreeMap<String, V>,
+}
+
+/// Handles of the saved entities mapped to the handles of the loaded ones.
+#[derive(Debug, Default)]
+pub struct EntityMap
+{
+    entities: HashMap<Entity, Entity>,
+}
+
+/// Component holding handles of other entities, which have to be remapped when it is loaded.
+pub trait MapEntities
+{
+    /// Replaces the saved handles with the loaded ones. Returns `false` if the component cannot be
+    /// kept, because it refers to an entity which was not a part of the snapshot.
+    fn map_entities(&mut self, map: &EntityMap) -> bool;
+}
+
+#[derive(Debug)]
+pub enum SnapshotError
+{
+    Json(serde_json::Error),
+    /// Invalid RON, together with the position of the error.
+    Ron(SpannedError),
+    /// The value could not be written as RON.
+    RonWrite(ron::Error),
+    /// The snapshot contains a component which was not made serializable in the world.
+    UnknownComponent(String),
+    /// The component could not be converted from or into a value.
+    Component
+    {
+        name: &'static str,
+        error: Box<SnapshotError>,
+    },
+}
+
+/// Deserialized component waiting for the entities of the snapshot to be spawned.
+pub(crate) type LoadedComponent = Box<dyn FnOnce(&mut World, Entity, &EntityMap)>;
+
+/// Type erased conversions of a serializable component.
+#[derive(Clone, Copy)]
+pub(crate) struct ComponentSerde
+{
+    pub name: &'static str,
+    pub save: fn(&World, Entity, SnapshotFormat) -> Option<Result<ComponentValue, SnapshotError>>,
+    pub load: fn(&ComponentValue) -> Result<LoadedComponent, SnapshotError>,
+}
+
+impl Snapshot
+{
+    /// Snapshot without entities, the components are saved in the `format`.
+    pub fn new(format: SnapshotFormat) -> Self
+    {
+        Self {
+            format,
+            entities: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> SnapshotFormat
+    {
+        self.format
+    }
+
+    /// Writes the snapshot in its format.
+    pub fn to_text(&self) -> Result<String, SnapshotError>
+    {
+        match self.format
+        {
+            SnapshotFormat::Json => serde_json::to_string_pretty(self).map_err(SnapshotError::Json),
+            SnapshotFormat::Ron =>
+            {
+                ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                    .map_err(SnapshotError::RonWrite)
+            }
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError>
+    {
+        let document: Document<Box<serde_json::value::RawValue>> =
+            serde_json::from_str(json).map_err(SnapshotError::Json)?;
+
+        Ok(Self::from_document(
+            document,
+            SnapshotFormat::Json,
+            ComponentValue::Json,
+        ))
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, SnapshotError>
+    {
+        let document: Document<Box<ron::value::RawValue>> =
+            ron::from_str(ron).map_err(SnapshotError::Ron)?;
+
+        Ok(Self::from_document(
+            document,
+            SnapshotFormat::Ron,
+            ComponentValue::Ron,
+        ))
+    }
+
+    fn from_document<V>(
+        document: Document<V>, format: SnapshotFormat, value: fn(V) -> ComponentValue,
+    ) -> Self
+    {
+        let entities = document
+            .entities
+            .into_iter()
+            .map(|saved| EntitySnapshot {
+                entity: saved.entity,
+                components: saved
+                    .components
+                    .into_iter()
+                    .map(|(name, component)| (name, value(component)))
+                    .collect(),
+            })
+            .collect();
+
+        Self { format, entities }
+    }
+}
+
+impl ComponentValue
+{
+    pub fn new<T: Serialize>(component: &T, format: SnapshotFormat) -> Result<Self, SnapshotError>
+    {
+        match format
+        {
+            SnapshotFormat::Json => serde_json::value::to_raw_value(component)
+                .map(Self::Json)
+                .map_err(SnapshotError::Json),
+            SnapshotFormat::Ron => ron::value::RawValue::from_rust(component)
+                .map(Self::Ron)
+                .map_err(SnapshotError::RonWrite),
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, SnapshotError>
+    {
+        match self
+        {
+            Self::Json(value) => serde_json::from_str(value.get()).map_err(SnapshotError::Json),
+            Self::Ron(value) => value.into_rust().map_err(SnapshotError::Ron),
+        }
+    }
+}
+
+impl Serialize for ComponentValue
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        match self
+        {
+            Self::Json(value) => value.serialize(serializer),
+            Self::Ron(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl EntityMap
+{
+    /// Returns the loaded entity, `None` for the entities which were not a part of the snapshot.
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Option<Entity>
+    {
+        self.entities.get(&entity).copied()
+    }
+
+    pub(crate) fn insert(&mut self, saved: Entity, loaded: Entity)
+    {
+        self.entities.insert(saved, loaded);
+    }
+}
+
+impl Display for SnapshotError
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult
+    {
+        match self
+        {
+            Self::Json(error) => write!(f, "Invalid JSON: {error}"),
+            Self::Ron(error) => write!(f, "Invalid RON: {error}"),
+            Self::RonWrite(error) => write!(f, "RON cannot be written: {error}"),
+            Self::UnknownComponent(name) => write!(f, "Unknown component `{name}`."),
+            Self::Component { name, error } => write!(f, "Invalid component `{name}`: {error}"),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl ComponentSerde
+{
+    pub fn new<T: Serialize + DeserializeOwned + Send + Sync + 'static>(name: &'static str)
+        -> Self
+    {
+        Self {
+            name,
+            save: save::<T>,
+            load: load::<T>,
+        }
+    }
+
+    pub fn with_entities<T>(name: &'static str) -> Self
+    where
+        T: MapEntities + Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        Self {
+            name,
+            save: save::<T>,
+            load: load_with_entities::<T>,
+        }
+    }
+}
+
+fn save<T: Serialize + 'static>(
+    world: &World, entity: Entity, format: SnapshotFormat,
+) -> Option<Result<ComponentValue, SnapshotError>>
+{
+    world
+        .get::<T>(entity)
+        .map(|component| ComponentValue::new(&*component, format))
+}
+
+fn load<T: DeserializeOwned + Send + Sync + 'static>(
+    value: &ComponentValue,
+) -> Result<LoadedComponent, SnapshotError>
+{
+    let component = value.deserialize::<T>()?;
+
+    Ok(Box::new(move |world: &mut World, entity, _: &EntityMap| {
+        world.insert_component(entity, component);
+    }))
+}
+
+fn load_with_entities<T: MapEntities + DeserializeOwned + Send + Sync + 'static>(
+    value: &ComponentValue,
+) -> Result<LoadedComponent, SnapshotError>
+{
+    let mut component = value.deserialize::<T>()?;
+
+    Ok(Box::new(
+        move |world: &mut World, entity, map: &EntityMap| {
+            if component.map_entities(map)
+            {
+                world.insert_component(entity, component);
+            }
+        },
+    ))
+}
+
+impl MapEntities for Entity
+{
+    fn map_entities(&mut self, map: &EntityMap) -> bool
+    {
+        map.get(*self).map(|loaded| *self = loaded).is_some()
+    }
+}
+
+// The entity saved without its parent is loaded as a root.
+impl MapEntities for Parent
+{
+    fn map_entities(&mut self, map: &EntityMap) -> bool
+    {
+        self.0.map_entities(map)
+    }
+}
+
+// Only the saved children are kept.
+impl MapEntities for Children
+{
+    fn map_entities(&mut self, map: &EntityMap) -> bool
+    {
+        self.0.retain_mut(|child| child.map_entities(map));
+        !self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::storage::{Storage, StorageType};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Position
+    {
+        x: f64,
+        y: f64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Name(String);
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum State
+    {
+        Idle,
+        Moving
+        {
+            speed: f64,
+        },
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Target(Entity);
+
+    struct Secret;
+
+    impl MapEntities for Target
+    {
+        fn map_entities(&mut self, map: &EntityMap) -> bool
+        {
+            self.0.map_entities(map)
+        }
+    }
+
+    fn world() -> World
+    {
+        World::new()
+            .serializable::<Position>("Position")
+            .serializable::<Name>("Name")
+            .serializable::<State>("State")
+            .serializable_with_entities::<Target>("Target")
+            .register::<Secret>()
+    }
+
+    fn populate(world: &mut World) -> (Entity, Entity)
+    {
+        let ship = world
+            .spawn()
+            .with(Position { x: 1.0, y: 2.0 })
+            .with(Name("ship".into()))
+            .with(Secret)
+            .into_id();
+        let turret = world
+            .spawn()
+            .with(Name("turret".into()))
+            .with(Target(ship))
+            .with_parent(ship)
+            .into_id();
+
+        (ship, turret)
+    }
+
+    fn check_loaded(world: &World, map: &EntityMap, (ship, turret): (Entity, Entity))
+    {
+        let (ship, turret) = (map.get(ship).unwrap(), map.get(turret).unwrap());
+
+        assert_eq!(
+            *world.get::<Position>(ship).unwrap(),
+            Position { x: 1.0, y: 2.0 }
+        );
+        assert_eq!(world.get::<Name>(turret).unwrap().0, "turret");
+        assert_eq!(world.get::<Target>(turret).unwrap().0, ship);
+        assert_eq!(world.get::<Parent>(turret).unwrap().get(), ship);
+        assert_eq!(world.get::<Children>(ship).unwrap().to_vec(), vec![turret]);
+        assert!(world.get::<Secret>(ship).is_none());
+    }
+
+    #[test]
+    fn save_and_load_json()
+    {
+        let mut saved = world();
+        let entities = populate(&mut saved);
+        let json = saved.save(SnapshotFormat::Json).unwrap().to_text().unwrap();
+
+        let mut loaded = world();
+        loaded.spawn().with(Name("already here".into()));
+
+        let map = loaded.load(&Snapshot::from_json(&json).unwrap()).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        check_loaded(&loaded, &map, entities);
+    }
+
+    #[test]
+    fn save_and_load_ron()
+    {
+        let mut saved = world();
+        let entities = populate(&mut saved);
+        let ron = saved.save(SnapshotFormat::Ron).unwrap().to_text().unwrap();
+
+        let mut loaded = world();
+        let map = loaded.load(&Snapshot::from_ron(&ron).unwrap()).unwrap();
+
+        check_loaded(&loaded, &map, entities);
+    }
+
+    #[test]
+    fn load_hand_written_ron()
+    {
+        let ron = r#"(
+            entities: [
+                (
+                    entity: (index: 4, generation: 1),
+                    components: {"Name": Name("runner"), "State": Moving(speed: 1.5)},
+                ),
+                (entity: (index: 7, generation: 0), components: {"State": Idle}),
+            ],
+        )"#;
+        let mut world = world();
+
+        let map = world.load(&Snapshot::from_ron(ron).unwrap()).unwrap();
+        let runner = map.get(Entity::new(4, 1)).unwrap();
+        let idle = map.get(Entity::new(7, 0)).unwrap();
+
+        assert_eq!(world.get::<Name>(runner).unwrap().0, "runner");
+        assert_eq!(
+            *world.get::<State>(runner).unwrap(),
+            State::Moving { speed: 1.5 }
+        );
+        assert_eq!(*world.get::<State>(idle).unwrap(), State::Idle);
+    }
+
+    #[test]
+    fn save_enums_as_ron()
+    {
+        let mut saved = world();
+        let moving = saved.spawn().with(State::Moving { speed: 2.0 }).into_id();
+        let idle = saved.spawn().with(State::Idle).into_id();
+
+        let ron = saved.save(SnapshotFormat::Ron).unwrap().to_text().unwrap();
+
+        assert!(ron.contains("\"State\": Moving(speed:2.0)"));
+        assert!(ron.contains("\"State\": Idle"));
+
+        let mut loaded = world();
+        let map = loaded.load(&Snapshot::from_ron(&ron).unwrap()).unwrap();
+
+        assert_eq!(
+            *loaded.get::<State>(map.get(moving).unwrap()).unwrap(),
+            State::Moving { speed: 2.0 }
+        );
+        assert_eq!(
+            *loaded.get::<State>(map.get(idle).unwrap()).unwrap(),
+            State::Idle
+        );
+    }
+
+    #[test]
+    fn ron_error_keeps_the_position()
+    {
+        let result = Snapshot::from_ron("(entities: [\n    (entity: oops)])");
+
+        assert!(matches!(result, Err(SnapshotError::Ron(error)) if error.span.start.line == 2));
+    }
+
+    #[test]
+    fn save_only_chosen_entities()
+    {
+        let mut saved = world();
+        let (ship, turret) = populate(&mut saved);
+        let snapshot = saved.save_entities([ship], SnapshotFormat::Json).unwrap();
+
+        assert_eq!(snapshot.entities.len(), 1);
+        assert_eq!(
+            snapshot.entities[0].components.keys().collect::<Vec<_>>(),
+            vec!["Children", "Name", "Position"]
+        );
+
+        let mut loaded = world();
+        let map = loaded.load(&snapshot).unwrap();
+        let loaded_ship = map.get(ship).unwrap();
+
+        assert_eq!(map.get(turret), None);
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.get::<Children>(loaded_ship).is_none());
+        assert_eq!(loaded.get::<Name>(loaded_ship).unwrap().0, "ship");
+
+        let snapshot = saved.save_entities([turret], SnapshotFormat::Ron).unwrap();
+        let map = loaded.load(&snapshot).unwrap();
+        let loaded_turret = map.get(turret).unwrap();
+
+        assert!(loaded.get::<Parent>(loaded_turret).is_none());
+        assert!(loaded.get::<Target>(loaded_turret).is_none());
+        assert_eq!(loaded.get::<Name>(loaded_turret).unwrap().0, "turret");
+    }
+
+    #[test]
+    fn serializable_does_not_choose_the_storage()
+    {
+        let world = World::new()
+            .serializable::<Position>("Position")
+            .register_with::<Position>(StorageType::Table);
+
+        assert!(matches!(
+            world.storage::<Position>(),
+            Some(Storage::Table(..))
+        ));
+    }
+
+    #[test]
+    fn reject_unknown_component()
+    {
+        let json = r#"{"entities": [{"entity": {"index": 0, "generation": 0}, "components": {"Health": 3}}]}"#;
+        let mut world = world();
+
+        let result = world.load(&Snapshot::from_json(json).unwrap());
+
+        assert!(matches!(result, Err(SnapshotError::UnknownComponent(name)) if name == "Health"));
+        assert!(world.is_empty());
+    }
+
+    #[test]
+    fn invalid_component_loads_nothing()
+    {
+        let json = r#"{"entities": [
+            {"entity": {"index": 0, "generation": 0}, "components": {"Name": "first"}},
+            {"entity": {"index": 1, "generation": 0}, "components": {"Name": 3}}
+        ]}"#;
+        let mut world = world();
+
+        let result = world.load(&Snapshot::from_json(json).unwrap());
+
+        assert!(matches!(
+            result,
+            Err(SnapshotError::Component { name: "Name", .. })
+        ));
+        assert!(world.is_empty());
+    }
+}
@@ -1,22 +1,67 @@
 use std::any::Any;
 
+use crate::cell::{AtomicRefCell, TickCell};
 use crate::entity::Entity;
 
-pub trait ComponentStorage
+const PAGE_SIZE: usize = 1024;
+
+pub trait ComponentStorage: Send + Sync
 {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn delete(&mut self, id: usize);
+    fn count(&self) -> usize;
+    /// Forgets the removals which happened at the `tick` or earlier.
+    fn clear_removed(&mut self, tick: u64);
+    fn delete(&mut self, entity: Entity, tick: u64);
+    fn entity_at(&self, index: usize) -> Entity;
+}
+
+#[derive(Debug)]
+pub struct Entry<T>
+{
+    pub entity: Entity,
+    pub item: AtomicRefCell<T>,
+    pub added: TickCell,
+    pub changed: TickCell,
+}
+
+/// Sparse array split into fixed size pages, a page is allocated when the first index inside of it
+/// gets a value and released once it is empty again.
+#[derive(Debug, Default)]
+struct Pages
+{
+    pages: Vec<Option<Page>>,
+}
+
+#[derive(Debug)]
+struct Page
+{
+    used: usize,
+    slots: Box<[Option<usize>]>,
 }
 
 #[derive(Debug)]
 pub struct SparseSet<T>
 {
-    dense: Vec<Entity<T>>,
-    sparse: Vec<Option<usize>>,
+    dense: Vec<Entry<T>>,
+    sparse: Pages,
+    removed: Vec<(Entity, u64)>,
 }
 
-impl<T: 'static> ComponentStorage for SparseSet<T>
+impl<T> Entry<T>
+{
+    pub const fn new(entity: Entity, item: T, tick: u64) -> Self
+    {
+        Self {
+            entity,
+            item: AtomicRefCell::new(item),
+            added: TickCell::new(tick),
+            changed: TickCell::new(tick),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> ComponentStorage for SparseSet<T>
 {
     #[inline]
     fn as_any(&self) -> &dyn Any
@@ -30,64 +75,170 @@ impl<T: 'static> ComponentStorage for SparseSet<T>
         self
     }
 
-    fn delete(&mut self, id: usize)
+    #[inline]
+    fn count(&self) -> usize
+    {
+        self.dense.len()
+    }
+
+    fn clear_removed(&mut self, tick: u64)
+    {
+        self.removed.retain(|(_, removed)| *removed > tick);
+    }
+
+    #[inline]
+    fn delete(&mut self, entity: Entity, tick: u64)
+    {
+        self.remove(entity, tick);
+    }
+
+    #[inline]
+    fn entity_at(&self, index: usize) -> Entity
+    {
+        self.dense[index].entity
+    }
+}
+
+impl Page
+{
+    fn new() -> Self
+    {
+        Self {
+            used: 0,
+            slots: vec![None; PAGE_SIZE].into_boxed_slice(),
+        }
+    }
+}
+
+impl Pages
+{
+    fn get(&self, index: usize) -> Option<usize>
+    {
+        let page = self.pages.get(index / PAGE_SIZE)?.as_ref()?;
+        page.slots[index % PAGE_SIZE]
+    }
+
+    fn set(&mut self, index: usize, value: usize)
     {
-        if let Some(index) = self.sparse[id]
+        let page_index = index / PAGE_SIZE;
+
+        if page_index >= self.pages.len()
+        {
+            self.pages.resize_with(page_index + 1, || None);
+        }
+
+        let page = self.pages[page_index].get_or_insert_with(Page::new);
+        let slot = &mut page.slots[index % PAGE_SIZE];
+
+        if slot.replace(value).is_none()
+        {
+            page.used += 1;
+        }
+    }
+
+    fn unset(&mut self, index: usize)
+    {
+        let page_index = index / PAGE_SIZE;
+
+        let Some(Some(page)) = self.pages.get_mut(page_index)
+        else
+        {
+            return;
+        };
+
+        if page.slots[index % PAGE_SIZE].take().is_some()
+        {
+            page.used -= 1;
+        }
+        if page.used == 0
         {
-            self.dense.swap_remove(index);
-
-            if index < self.dense.len()
-            {
-                let moved = &self.dense[index];
-                self.sparse[moved.id] = Some(index);
-            }
-            self.sparse[id] = None;
+            self.pages[page_index] = None;
         }
     }
+
+    #[cfg(test)]
+    fn allocated(&self) -> usize
+    {
+        self.pages.iter().flatten().count()
+    }
+}
+
+impl<T> Default for SparseSet<T>
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
 }
 
 impl<T> SparseSet<T>
 {
-    pub fn new(size: usize) -> Self
+    pub fn new() -> Self
     {
         Self {
-            dense: Vec::with_capacity(size),
-            sparse: vec![None; size],
+            dense: Vec::new(),
+            sparse: Pages::default(),
+            removed: Vec::new(),
         }
     }
 
-    pub fn add(&mut self, id: usize, item: T)
+    /// Takes the entity component out, the removal is recorded.
+    pub fn remove(&mut self, entity: Entity, tick: u64) -> Option<T>
     {
-        if let Some(stored) = self.get(id)
+        let index = self.index_of(entity)?;
+        let entry = self.dense.swap_remove(index);
+
+        if index < self.dense.len()
+        {
+            let moved = &self.dense[index];
+            self.sparse.set(moved.entity.index(), index);
+        }
+        self.sparse.unset(entity.index());
+        self.removed.push((entity, tick));
+
+        Some(entry.item.into_inner())
+    }
+
+    /// Adds or replaces the entity component, replacing counts as a change.
+    pub fn add(&mut self, entity: Entity, item: T, tick: u64)
+    {
+        if let Some(stored) = self.get(entity)
         {
             stored.item.replace(item);
+            stored.changed.set(tick);
             return;
         }
 
-        let index = Some(self.dense.len());
+        let index = self.dense.len();
 
-        self.dense.push(Entity::new(id, item));
-        self.sparse[id] = index;
+        self.dense.push(Entry::new(entity, item, tick));
+        self.sparse.set(entity.index(), index);
     }
 
-    pub fn get(&self, id: usize) -> Option<&Entity<T>>
+    pub fn get(&self, entity: Entity) -> Option<&Entry<T>>
     {
-        let index = self.sparse[id]?;
+        let index = self.index_of(entity)?;
         let entry = &self.dense[index];
 
         Some(entry)
     }
 
+    /// Entities which lost the component, with the ticks of the removals.
+    #[inline]
+    pub fn removed(&self) -> &[(Entity, u64)]
+    {
+        &self.removed
+    }
+
     #[inline]
-    pub fn get_all(&self) -> &[Entity<T>]
+    pub fn get_all(&self) -> &[Entry<T>]
     {
         self.dense.as_slice()
     }
 
-    #[cfg(test)]
-    pub fn contains(&self, id: usize) -> bool
+    pub fn contains(&self, entity: Entity) -> bool
     {
-        self.sparse[id].is_some()
+        self.index_of(entity).is_some()
     }
 
     #[cfg(test)]
@@ -95,132 +246,207 @@ impl<T> SparseSet<T>
     {
         self.dense.len()
     }
+
+    // Entries of the older generations are never returned, even if they were not deleted.
+    fn index_of(&self, entity: Entity) -> Option<usize>
+    {
+        let index = self.sparse.get(entity.index())?;
+
+        (self.dense[index].entity == entity).then_some(index)
+    }
 }
 
 #[cfg(test)]
-
 mod tests
 {
-    use std::cell::RefCell;
-
     use super::*;
+    use crate::cell::AtomicRefCell;
+
+    fn entity(index: u32) -> Entity
+    {
+        Entity::new(index, 0)
+    }
 
     #[test]
-    #[should_panic]
-    fn crash_when_adding_more_than_capacity_would_fit()
+    fn grow_when_adding_past_allocated_pages()
     {
-        let mut set = SparseSet::new(1);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
-        set.add(1, 2);
+        set.add(entity(0), 1, 0);
+        set.add(entity(10 * PAGE_SIZE as u32), 2, 0);
+
+        assert_eq!(set.size(), 2);
+        assert_eq!(set.sparse.allocated(), 2);
+        assert_eq!(
+            set.get(entity(10 * PAGE_SIZE as u32)).unwrap().item,
+            AtomicRefCell::new(2)
+        );
+    }
+
+    #[test]
+    fn release_page_when_emptied()
+    {
+        let mut set = SparseSet::new();
+
+        set.add(entity(3 * PAGE_SIZE as u32), 1, 0);
+        set.add(entity(3 * PAGE_SIZE as u32 + 1), 2, 0);
+        set.delete(entity(3 * PAGE_SIZE as u32), 0);
+
+        assert_eq!(set.sparse.allocated(), 1);
+
+        set.delete(entity(3 * PAGE_SIZE as u32 + 1), 0);
+
+        assert_eq!(set.sparse.allocated(), 0);
     }
 
     #[test]
     fn add_new_element()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
 
-        set.add(5, 1);
+        set.add(entity(5), 1, 0);
 
-        assert_eq!(set.sparse[5].unwrap(), 0);
-        assert_eq!(set.dense[0].item, RefCell::new(1));
+        assert_eq!(set.sparse.get(5).unwrap(), 0);
+        assert_eq!(set.dense[0].item, AtomicRefCell::new(1));
     }
 
     #[test]
     fn add_element_which_already_exists()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
 
-        set.add(5, 1);
-        set.add(5, 2);
+        set.add(entity(5), 1, 0);
+        set.add(entity(5), 2, 0);
 
-        assert_eq!(set.sparse[5].unwrap(), 0);
-        assert_eq!(set.dense[0].item, RefCell::new(2));
+        assert_eq!(set.sparse.get(5).unwrap(), 0);
+        assert_eq!(set.dense[0].item, AtomicRefCell::new(2));
     }
 
     #[test]
     fn contains_returns_true_if_id_in_sparse()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
+        set.add(entity(0), 1, 0);
 
-        assert_eq!(set.contains(0), true);
+        assert!(set.contains(entity(0)));
     }
 
     #[test]
     fn contains_returns_false_if_id_not_in_sparse()
     {
-        let mut set = SparseSet::new(2);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
+        set.add(entity(0), 1, 0);
 
-        assert_eq!(set.contains(1), false);
+        assert!(!set.contains(entity(1)));
     }
 
     #[test]
     fn delete_does_nothing_when_not_exists()
     {
-        let mut set = SparseSet::new(2);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
-        set.delete(1);
+        set.add(entity(0), 1, 0);
+        set.delete(entity(1), 0);
 
-        assert_eq!(set.contains(0), true);
+        assert!(set.contains(entity(0)));
     }
 
     #[test]
     fn delete_when_only_one_element()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
 
-        set.add(4, 1);
-        set.delete(4);
+        set.add(entity(4), 1, 0);
+        set.delete(entity(4), 0);
 
-        assert_eq!(set.contains(4), false);
+        assert!(!set.contains(entity(4)));
     }
 
     #[test]
     fn delete_swaps_with_last_element()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
+
+        set.add(entity(4), 1, 0);
+        set.add(entity(7), 2, 0);
+        set.delete(entity(4), 0);
+
+        assert!(!set.contains(entity(4)));
+        assert_eq!(set.get(entity(7)).unwrap().item, AtomicRefCell::new(2));
+        assert_eq!(set.sparse.get(7).unwrap(), 0);
+    }
+
+    #[test]
+    fn delete_records_removal()
+    {
+        let mut set = SparseSet::new();
+
+        set.add(entity(1), 1, 0);
+        set.add(entity(2), 2, 0);
+        set.delete(entity(1), 3);
+        set.delete(entity(2), 5);
+
+        assert_eq!(set.removed(), &[(entity(1), 3), (entity(2), 5)]);
+
+        set.clear_removed(4);
+
+        assert_eq!(set.removed(), &[(entity(2), 5)]);
+    }
+
+    #[test]
+    fn replace_marks_entry_as_changed()
+    {
+        let mut set = SparseSet::new();
+
+        set.add(entity(1), 1, 2);
+        set.add(entity(1), 5, 7);
+
+        let entry = set.get(entity(1)).unwrap();
+
+        assert_eq!(entry.added.get(), 2);
+        assert_eq!(entry.changed.get(), 7);
+    }
+
+    #[test]
+    fn get_ignores_entry_of_another_generation()
+    {
+        let mut set = SparseSet::new();
 
-        set.add(4, 1);
-        set.add(7, 2);
-        set.delete(4);
+        set.add(entity(3), 1, 0);
 
-        assert_eq!(set.contains(4), false);
-        assert_eq!(set.get(7).unwrap().item, RefCell::new(2));
-        assert_eq!(set.sparse[7].unwrap(), 0);
+        assert!(set.get(Entity::new(3, 1)).is_none());
+        assert!(!set.contains(Entity::new(3, 1)));
     }
 
     #[test]
     fn get_returns_null_when_not_present()
     {
-        let mut set = SparseSet::new(2);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
+        set.add(entity(0), 1, 0);
 
-        assert_eq!(set.get(1).is_none(), true);
+        assert!(set.get(entity(1)).is_none());
     }
 
     #[test]
     fn get_returns_ref_when_present()
     {
-        let mut set = SparseSet::new(1);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
+        set.add(entity(0), 1, 0);
 
-        assert_eq!(set.get(0).unwrap().item, RefCell::new(1));
+        assert_eq!(set.get(entity(0)).unwrap().item, AtomicRefCell::new(1));
     }
 
     #[test]
     fn size_returns_current_set_size()
     {
-        let mut set = SparseSet::new(10);
+        let mut set = SparseSet::new();
 
-        set.add(0, 1);
-        set.add(1, 2);
+        set.add(entity(0), 1, 0);
+        set.add(entity(1), 2, 0);
 
         assert_eq!(set.size(), 2);
     }
@@ -0,0 +1,316 @@
+use crate::cell::{AtomicRefCell, Ref, RefMut, TickCell};
+use crate::entity::Entity;
+use crate::sparse_set::{ComponentStorage, Entry, SparseSet};
+use crate::table::{Archetype, ColumnMut, ColumnRef, TableColumn, Tables};
+
+/// Where the components of a type are kept, chosen when the type is registered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StorageType
+{
+    /// Every component type in its own sparse set. Adding and removing components is cheap.
+    #[default]
+    SparseSet,
+    /// Entities with the same set of table components are packed together, so iterating over
+    /// several components walks the memory in order. Adding and removing components moves all of
+    /// the entity table components. The components are borrowed a whole column at a time, by a
+    /// query for as long as it lives.
+    Table,
+}
+
+/// Cells of a single stored component.
+pub struct ComponentCells<'w, T>
+{
+    pub item: ItemCell<'w, T>,
+    pub added: &'w TickCell,
+    pub changed: &'w TickCell,
+}
+
+/// Component of a sparse set, borrowed on its own, or the row of a table column, borrowed together
+/// with the whole column.
+pub enum ItemCell<'w, T>
+{
+    Entry(&'w AtomicRefCell<T>),
+    Row(&'w TableColumn<T>, usize),
+}
+
+/// Read only view of the storage of a `T` component. The sparse set is kept also as the type
+/// erased storage, so the `T` does not need to be thread safe to drive a query.
+pub enum Storage<'w, T>
+{
+    Sparse(&'w SparseSet<T>, &'w dyn ComponentStorage),
+    Table(&'w Tables, usize),
+}
+
+/// Storage of a `T` component seen from a single archetype, a table column is indexed by the
+/// entity row instead of looking the entity up.
+pub enum ArchetypeStorage<'w, T>
+{
+    Sparse(&'w SparseSet<T>),
+    Table(&'w TableColumn<T>),
+}
+
+/// Storage of a `T` component borrowed by a query, the `C` being the borrow of a table column.
+/// Every column is borrowed once, when the query is created, and released together with the
+/// query, so the rows are fetched without touching the shared counters.
+pub struct StorageBorrow<'w, T, C>
+{
+    storage: Storage<'w, T>,
+    /// Indexed by the archetype, `None` if the column was already borrowed elsewhere.
+    columns: Vec<Option<C>>,
+}
+
+pub type StorageRef<'w, T> = StorageBorrow<'w, T, ColumnRef<'w, T>>;
+
+pub type StorageMut<'w, T> = StorageBorrow<'w, T, ColumnMut<'w, T>>;
+
+/// Borrowed storage seen from a single archetype. The table column is `None` if it was already
+/// borrowed elsewhere, so none of its components can be fetched.
+pub enum BorrowedRows<'q, T, C>
+{
+    Sparse(&'q SparseSet<T>),
+    Table(Option<&'q C>),
+}
+
+/// Storage offered by a query to drive the iteration, the smallest one wins.
+#[derive(Clone, Copy)]
+pub enum Candidate<'w>
+{
+    Sparse(&'w dyn ComponentStorage),
+    Table(&'w Tables, usize),
+}
+
+impl<T> Clone for Storage<'_, T>
+{
+    fn clone(&self) -> Self
+    {
+        *self
+    }
+}
+
+impl<T> Copy for Storage<'_, T> {}
+
+impl<'w, T: 'static> Storage<'w, T>
+{
+    pub fn get(&self, entity: Entity) -> Option<ComponentCells<'w, T>>
+    {
+        match self
+        {
+            Self::Sparse(storage, _) => storage.get(entity).map(entry_cells),
+            Self::Table(tables, id) => tables.get(*id, entity),
+        }
+    }
+
+    /// Returns `None` if the entities of the archetype cannot have the component.
+    pub fn in_archetype(&self, archetype: &'w Archetype) -> Option<ArchetypeStorage<'w, T>>
+    {
+        match self
+        {
+            Self::Sparse(storage, _) => Some(ArchetypeStorage::Sparse(storage)),
+            Self::Table(_, id) => archetype.column::<T>(*id).map(ArchetypeStorage::Table),
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: Entity) -> bool
+    {
+        self.get(entity).is_some()
+    }
+
+    pub fn candidate(&self) -> Candidate<'w>
+    {
+        match self
+        {
+            Self::Sparse(_, erased) => Candidate::Sparse(*erased),
+            Self::Table(tables, id) => Candidate::Table(tables, *id),
+        }
+    }
+
+    /// Entities which lost the component after the `tick`.
+    pub fn removed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + 'w
+    {
+        let removed = match self
+        {
+            Self::Sparse(storage, _) => storage.removed(),
+            Self::Table(tables, id) => tables.removed(*id),
+        };
+
+        removed
+            .iter()
+            .filter(move |(_, at)| *at > tick)
+            .map(|(entity, _)| *entity)
+    }
+}
+
+impl<'w, T: 'static> ItemCell<'w, T>
+{
+    pub fn try_borrow(&self) -> Option<Ref<'w, T>>
+    {
+        match self
+        {
+            Self::Entry(item) => item.try_borrow(),
+            Self::Row(column, row) => column.try_borrow(*row),
+        }
+    }
+
+    pub fn try_borrow_mut(&self) -> Option<RefMut<'w, T>>
+    {
+        match self
+        {
+            Self::Entry(item) => item.try_borrow_mut(),
+            Self::Row(column, row) => column.try_borrow_mut(*row),
+        }
+    }
+
+    pub fn borrow(&self) -> Ref<'w, T>
+    {
+        match self
+        {
+            Self::Entry(item) => item.borrow(),
+            Self::Row(column, row) => column.borrow(*row),
+        }
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'w, T>
+    {
+        match self
+        {
+            Self::Entry(item) => item.borrow_mut(),
+            Self::Row(column, row) => column.borrow_mut(*row),
+        }
+    }
+}
+
+impl<'w, T: 'static, C> StorageBorrow<'w, T, C>
+{
+    /// Borrows every table column of the component with the `borrow`.
+    pub fn new(storage: Storage<'w, T>, borrow: impl Fn(&'w TableColumn<T>) -> Option<C>) -> Self
+    {
+        let mut columns = Vec::new();
+
+        if let Storage::Table(tables, id) = storage
+        {
+            for archetype in tables.archetypes_with(id)
+            {
+                let index = archetype.index();
+
+                if columns.len() <= index
+                {
+                    columns.resize_with(index + 1, || None);
+                }
+                columns[index] = archetype.column::<T>(id).and_then(&borrow);
+            }
+        }
+        Self { storage, columns }
+    }
+
+    #[inline]
+    pub fn storage(&self) -> Storage<'w, T>
+    {
+        self.storage
+    }
+
+    /// Rows of the archetype of the entity together with the entity row, `None` if the entity
+    /// does not have the component. A sparse set is returned whole, it finds the entity by itself.
+    pub fn get(&self, entity: Entity) -> Option<(BorrowedRows<'_, T, C>, usize)>
+    {
+        match self.storage
+        {
+            Storage::Sparse(storage, _) => Some((BorrowedRows::Sparse(storage), 0)),
+            Storage::Table(tables, id) =>
+            {
+                let (archetype, row) = tables.row_of(id, entity)?;
+                Some((BorrowedRows::Table(self.column(archetype)), row))
+            }
+        }
+    }
+
+    /// Returns `None` if the entities of the archetype cannot have the component.
+    pub fn in_archetype(&self, archetype: &Archetype) -> Option<BorrowedRows<'_, T, C>>
+    {
+        match self.storage
+        {
+            Storage::Sparse(storage, _) => Some(BorrowedRows::Sparse(storage)),
+            Storage::Table(_, id) => archetype
+                .contains(id)
+                .then(|| BorrowedRows::Table(self.column(archetype.index()))),
+        }
+    }
+
+    fn column(&self, archetype: usize) -> Option<&C>
+    {
+        self.columns.get(archetype).and_then(Option::as_ref)
+    }
+}
+
+impl<T> Clone for ArchetypeStorage<'_, T>
+{
+    fn clone(&self) -> Self
+    {
+        *self
+    }
+}
+
+impl<T> Copy for ArchetypeStorage<'_, T> {}
+
+impl<'w, T: 'static> ArchetypeStorage<'w, T>
+{
+    /// Component of the `entity` stored at the `row` of the archetype.
+    #[inline]
+    pub fn get(&self, entity: Entity, row: usize) -> Option<ComponentCells<'w, T>>
+    {
+        match self
+        {
+            Self::Sparse(storage) => storage.get(entity).map(entry_cells),
+            Self::Table(column) => Some(column.cells(row)),
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: Entity) -> bool
+    {
+        match self
+        {
+            Self::Sparse(storage) => storage.get(entity).is_some(),
+            Self::Table(_) => true,
+        }
+    }
+}
+
+impl<'w> Candidate<'w>
+{
+    pub fn count(&self) -> usize
+    {
+        match self
+        {
+            Self::Sparse(storage) => storage.count(),
+            Self::Table(tables, id) => tables.count(*id),
+        }
+    }
+
+    /// Keeps the smaller of the candidates.
+    pub fn narrow(driver: &mut Option<Candidate<'w>>, candidate: Candidate<'w>)
+    {
+        if driver.is_none_or(|current| candidate.count() < current.count())
+        {
+            *driver = Some(candidate);
+        }
+    }
+
+    pub(crate) fn archetypes(&self) -> Vec<&'w Archetype>
+    {
+        match self
+        {
+            Self::Sparse(_) => Vec::new(),
+            Self::Table(tables, id) => tables.archetypes_with(*id).collect(),
+        }
+    }
+}
+
+fn entry_cells<T>(entry: &Entry<T>) -> ComponentCells<'_, T>
+{
+    ComponentCells {
+        item: ItemCell::Entry(&entry.item),
+        added: &entry.added,
+        changed: &entry.changed,
+    }
+}
@@ -0,0 +1,311 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use crate::change::Ticks;
+use crate::query::{Access, Query, QueryData, QueryFilter};
+use crate::world::World;
+
+/// Value a system function can take as an argument, fetched from the world every time it runs.
+pub trait SystemParam
+{
+    /// Data kept by the system between the runs.
+    type State: Send + 'static;
+    type Item<'w, 's>;
+
+    fn access(access: &mut Access);
+
+    fn init(world: &mut World) -> Self::State;
+
+    fn fetch<'w, 's>(
+        state: &'s mut Self::State, world: &'w World, ticks: Ticks,
+    ) -> Self::Item<'w, 's>;
+
+    /// Applies the changes deferred by the system, called at the end of the stage.
+    fn apply(_state: &mut Self::State, _world: &mut World) {}
+}
+
+pub type SystemParamItem<'w, 's, P> = <P as SystemParam>::Item<'w, 's>;
+
+pub trait System: Send
+{
+    fn name(&self) -> &'static str;
+
+    /// Components and resources borrowed by the system, known once it is initialized. Returns
+    /// `None` if the system needs the whole world mutably.
+    fn access(&self) -> Option<&Access>;
+
+    /// Called once, before the first run. Panics if the system borrows any component mutably
+    /// more than once.
+    fn initialize(&mut self, world: &mut World);
+
+    fn run(&mut self, world: &mut World);
+
+    /// Runs the system while the world is shared with other systems. Panics if the system has
+    /// no `access`.
+    fn run_shared(&mut self, world: &World);
+
+    /// Applies the commands recorded by the system since the last call.
+    fn apply_deferred(&mut self, _world: &mut World) {}
+}
+
+pub trait IntoSystem<Marker>
+{
+    type System: System + 'static;
+
+    fn into_system(self) -> Self::System;
+}
+
+/// Function taking only system parameters, called with the values fetched from the world.
+pub trait SystemParamFunction<P: SystemParam>: Send + 'static
+{
+    fn call(&mut self, params: SystemParamItem<'_, '_, P>);
+}
+
+pub struct FunctionSystem<F, P: SystemParam>
+{
+    function: F,
+    state: Option<P::State>,
+    access: Access,
+    last_run: u64,
+    marker: PhantomData<fn() -> P>,
+}
+
+/// System taking the whole world mutably, it can spawn and despawn entities.
+pub struct ExclusiveSystem<F>
+{
+    function: F,
+}
+
+#[doc(hidden)]
+pub struct FunctionMarker;
+
+#[doc(hidden)]
+pub struct ExclusiveMarker;
+
+impl<F, P> System for FunctionSystem<F, P>
+where
+    F: SystemParamFunction<P>,
+    P: SystemParam + 'static,
+{
+    #[inline]
+    fn name(&self) -> &'static str
+    {
+        type_name::<F>()
+    }
+
+    #[inline]
+    fn access(&self) -> Option<&Access>
+    {
+        Some(&self.access)
+    }
+
+    fn initialize(&mut self, world: &mut World)
+    {
+        let mut access = Access::default();
+        P::access(&mut access);
+
+        if let Some(component) = access.conflict()
+        {
+            panic!(
+                "System `{}` borrows `{}` mutably while it is already borrowed.",
+                self.name(),
+                component
+            );
+        }
+        self.access = access;
+        self.state = Some(P::init(world));
+    }
+
+    #[inline]
+    fn run(&mut self, world: &mut World)
+    {
+        self.run_shared(world);
+    }
+
+    fn run_shared(&mut self, world: &World)
+    {
+        let state = self
+            .state
+            .as_mut()
+            .expect("The system has to be initialized before it runs.");
+
+        let this_run = world.increment_change_tick();
+        let ticks = Ticks {
+            last_run: self.last_run,
+            this_run,
+        };
+
+        let params = P::fetch(state, world, ticks);
+        self.function.call(params);
+        self.last_run = this_run;
+    }
+
+    fn apply_deferred(&mut self, world: &mut World)
+    {
+        if let Some(state) = &mut self.state
+        {
+            P::apply(state, world);
+        }
+    }
+}
+
+impl<F, P> IntoSystem<(FunctionMarker, P)> for F
+where
+    F: SystemParamFunction<P>,
+    P: SystemParam + 'static,
+{
+    type System = FunctionSystem<F, P>;
+
+    fn into_system(self) -> Self::System
+    {
+        FunctionSystem {
+            function: self,
+            state: None,
+            access: Access::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> System for ExclusiveSystem<F>
+{
+    #[inline]
+    fn name(&self) -> &'static str
+    {
+        type_name::<F>()
+    }
+
+    #[inline]
+    fn access(&self) -> Option<&Access>
+    {
+        None
+    }
+
+    fn initialize(&mut self, _: &mut World) {}
+
+    fn run(&mut self, world: &mut World)
+    {
+        world.increment_change_tick();
+        (self.function)(world);
+    }
+
+    fn run_shared(&mut self, _: &World)
+    {
+        panic!("Exclusive system `{}` cannot share the world.", self.name());
+    }
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> IntoSystem<ExclusiveMarker> for F
+{
+    type System = ExclusiveSystem<F>;
+
+    fn into_system(self) -> Self::System
+    {
+        ExclusiveSystem { function: self }
+    }
+}
+
+impl SystemParam for &World
+{
+    type Item<'w, 's> = &'w World;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        access.read_world();
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, _: Ticks) -> Self::Item<'w, 's>
+    {
+        world
+    }
+}
+
+impl<D, F> SystemParam for Query<'_, D, F>
+where
+    D: QueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    type Item<'w, 's> = Query<'w, D, F>;
+    type State = ();
+
+    fn access(access: &mut Access)
+    {
+        D::access(access);
+    }
+
+    fn init(_: &mut World) -> Self::State {}
+
+    fn fetch<'w, 's>(_: &'s mut Self::State, world: &'w World, ticks: Ticks) -> Self::Item<'w, 's>
+    {
+        Query::new(world, ticks)
+    }
+}
+
+macro_rules! impl_system_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        impl<$($name: SystemParam),*> SystemParam for ($($name,)*)
+        {
+            type Item<'w, 's> = ($($name::Item<'w, 's>,)*);
+            type State = ($($name::State,)*);
+
+            fn access(access: &mut Access)
+            {
+                $($name::access(access);)*
+            }
+
+            fn init(world: &mut World) -> Self::State
+            {
+                ($($name::init(world),)*)
+            }
+
+            fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World, ticks: Ticks)
+                -> Self::Item<'w, 's>
+            {
+                let ($($name,)*) = state;
+                ($($name::fetch($name, world, ticks),)*)
+            }
+
+            fn apply(state: &mut Self::State, world: &mut World)
+            {
+                let ($($name,)*) = state;
+                $($name::apply($name, world);)*
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<Func, $($name: SystemParam),*> SystemParamFunction<($($name,)*)> for Func
+        where
+            Func: Send + 'static,
+            for<'a> &'a mut Func:
+                FnMut($($name),*) + FnMut($(SystemParamItem<'_, '_, $name>),*),
+        {
+            fn call(&mut self, params: SystemParamItem<'_, '_, ($($name,)*)>)
+            {
+                // Helps the compiler to pick the signature taking the fetched items.
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<$($name),*>(mut function: impl FnMut($($name),*), $($name: $name),*)
+                {
+                    function($($name),*)
+                }
+
+                let ($($name,)*) = params;
+                call_inner(self, $($name),*)
+            }
+        }
+    };
+}
+
+impl_system_tuple!();
+impl_system_tuple!(A);
+impl_system_tuple!(A, B);
+impl_system_tuple!(A, B, C);
+impl_system_tuple!(A, B, C, D);
+impl_system_tuple!(A, B, C, D, E);
+impl_system_tuple!(A, B, C, D, E, G);
+impl_system_tuple!(A, B, C, D, E, G, H);
+impl_system_tuple!(A, B, C, D, E, G, H, I);
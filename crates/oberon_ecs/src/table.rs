@@ -0,0 +1,751 @@
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Cell, UnsafeCell};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::cell::{BorrowFlag, Ref, RefMut, TickCell};
+use crate::entity::Entity;
+use crate::storage::{ComponentCells, ItemCell};
+
+/// Type erased column of a single component type.
+pub(crate) trait Column: Send + Sync
+{
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn empty(&self) -> Box<dyn Column>;
+    /// Moves the row to the end of the `target` column of the same type.
+    fn move_row(&mut self, row: usize, target: &mut dyn Column);
+    fn swap_remove(&mut self, row: usize);
+}
+
+/// Components of a single type stored in an archetype, indexed by the entity row. The column is
+/// borrowed as a whole, a query borrows it once and then hands out the rows without touching the
+/// shared counter.
+pub struct TableColumn<T>
+{
+    borrows: BorrowFlag,
+    items: Vec<UnsafeCell<T>>,
+    /// Rows handed out mutably by the query which borrows the column exclusively.
+    claims: Vec<BorrowFlag>,
+    added: Vec<TickCell>,
+    changed: Vec<TickCell>,
+}
+
+/// Shared borrow of a whole column, released on drop.
+pub struct ColumnRef<'w, T>
+{
+    column: &'w TableColumn<T>,
+}
+
+/// Exclusive borrow of a whole column, released on drop. The rows are claimed one by one, so they
+/// cannot be handed out mutably twice. Claiming is not atomic, hence the borrow stays on a single
+/// thread.
+pub struct ColumnMut<'w, T>
+{
+    column: &'w TableColumn<T>,
+    _single_thread: PhantomData<Cell<()>>,
+}
+
+/// Entities having exactly the same set of table components, every component is stored in its own
+/// column and the entity components share the row.
+pub struct Archetype
+{
+    index: usize,
+    components: Vec<usize>,
+    columns: Vec<Box<dyn Column>>,
+    column_of: Vec<Option<usize>>,
+    /// Archetypes the entities move to when they get a component, indexed by the component.
+    adding: Vec<Option<usize>>,
+    entities: Vec<Entity>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Location
+{
+    archetype: usize,
+    row: usize,
+}
+
+/// Archetype storage of all the components registered with `StorageType::Table`.
+#[derive(Default)]
+pub struct Tables
+{
+    ids: HashMap<TypeId, usize>,
+    empty_columns: Vec<Box<dyn Column>>,
+    containing: Vec<Vec<usize>>,
+    removed: Vec<Vec<(Entity, u64)>>,
+    archetypes: Vec<Archetype>,
+    archetype_of: HashMap<Vec<usize>, usize>,
+    locations: Vec<Option<Location>>,
+}
+
+// The borrows counter of the column guarantees that the rows are never aliased mutably.
+unsafe impl<T: Send> Send for TableColumn<T> {}
+unsafe impl<T: Send + Sync> Sync for TableColumn<T> {}
+
+impl<T: 'static> TableColumn<T>
+{
+    fn new() -> Self
+    {
+        Self {
+            borrows: BorrowFlag::new(),
+            items: Vec::new(),
+            claims: Vec::new(),
+            added: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, item: T, added: u64, changed: u64)
+    {
+        self.items.push(UnsafeCell::new(item));
+        self.claims.push(BorrowFlag::new());
+        self.added.push(TickCell::new(added));
+        self.changed.push(TickCell::new(changed));
+    }
+
+    #[inline]
+    pub fn cells(&self, row: usize) -> ComponentCells<'_, T>
+    {
+        ComponentCells {
+            item: ItemCell::Row(self, row),
+            added: &self.added[row],
+            changed: &self.changed[row],
+        }
+    }
+
+    /// Borrows the whole column for the component at the `row`, returns `None` if the column is
+    /// borrowed mutably.
+    pub fn try_borrow(&self, row: usize) -> Option<Ref<'_, T>>
+    {
+        self.borrows.try_share().then(|| {
+            // SAFETY: The counter excludes the mutable borrows until the `Ref` is dropped.
+            Ref::new(unsafe { &*self.items[row].get() }, Some(&self.borrows))
+        })
+    }
+
+    /// Borrows the whole column mutably for the component at the `row`, returns `None` if the
+    /// column is borrowed.
+    pub fn try_borrow_mut(&self, row: usize) -> Option<RefMut<'_, T>>
+    {
+        self.borrows.try_exclusive().then(|| {
+            // SAFETY: The counter excludes any other borrow until the `RefMut` is dropped.
+            RefMut::new(unsafe { &mut *self.items[row].get() }, &self.borrows)
+        })
+    }
+
+    /// Panics if the column is borrowed mutably.
+    pub fn borrow(&self, row: usize) -> Ref<'_, T>
+    {
+        self.try_borrow(row).unwrap_or_else(|| {
+            panic!(
+                "The column of `{}` is already borrowed mutably.",
+                type_name::<T>()
+            )
+        })
+    }
+
+    /// Panics if the column is borrowed.
+    pub fn borrow_mut(&self, row: usize) -> RefMut<'_, T>
+    {
+        self.try_borrow_mut(row)
+            .unwrap_or_else(|| panic!("The column of `{}` is already borrowed.", type_name::<T>()))
+    }
+
+    /// Returns `None` if the column is borrowed mutably.
+    pub fn try_borrow_column(&self) -> Option<ColumnRef<'_, T>>
+    {
+        self.borrows
+            .try_share()
+            .then_some(ColumnRef { column: self })
+    }
+
+    /// Returns `None` if the column is borrowed.
+    pub fn try_borrow_column_mut(&self) -> Option<ColumnMut<'_, T>>
+    {
+        self.borrows.try_exclusive().then_some(ColumnMut {
+            column: self,
+            _single_thread: PhantomData,
+        })
+    }
+
+    fn take(&mut self, row: usize) -> T
+    {
+        self.claims.swap_remove(row);
+        self.added.swap_remove(row);
+        self.changed.swap_remove(row);
+        self.items.swap_remove(row).into_inner()
+    }
+}
+
+impl<T> ColumnRef<'_, T>
+{
+    #[inline]
+    pub fn get(&self, row: usize) -> &T
+    {
+        // SAFETY: The column is borrowed, so nobody writes to it.
+        unsafe { &*self.column.items[row].get() }
+    }
+}
+
+impl<T> Drop for ColumnRef<'_, T>
+{
+    fn drop(&mut self)
+    {
+        self.column.borrows.release_shared();
+    }
+}
+
+impl<T> ColumnMut<'_, T>
+{
+    /// Returns `None` if the component at the `row` is already handed out.
+    #[inline]
+    pub fn get(&self, row: usize) -> Option<RefMut<'_, T>>
+    {
+        let claim = &self.column.claims[row];
+
+        claim.try_claim().then(|| {
+            // SAFETY: The column is borrowed exclusively and the row is claimed until the
+            // `RefMut` is dropped.
+            RefMut::new(unsafe { &mut *self.column.items[row].get() }, claim)
+        })
+    }
+
+    #[inline]
+    pub fn changed(&self, row: usize) -> &TickCell
+    {
+        &self.column.changed[row]
+    }
+}
+
+impl<T> Drop for ColumnMut<'_, T>
+{
+    fn drop(&mut self)
+    {
+        self.column.borrows.release_exclusive();
+    }
+}
+
+impl<T: Send + Sync + 'static> Column for TableColumn<T>
+{
+    #[inline]
+    fn as_any(&self) -> &dyn Any
+    {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any
+    {
+        self
+    }
+
+    fn empty(&self) -> Box<dyn Column>
+    {
+        Box::new(Self::new())
+    }
+
+    fn move_row(&mut self, row: usize, target: &mut dyn Column)
+    {
+        let target = target
+            .as_any_mut()
+            .downcast_mut::<Self>()
+            .expect("Rows can be moved only between the columns of the same type.");
+
+        let item = self.items.swap_remove(row).into_inner();
+        let added = self.added.swap_remove(row).get();
+        let changed = self.changed.swap_remove(row).get();
+        self.claims.swap_remove(row);
+
+        target.push(item, added, changed);
+    }
+
+    fn swap_remove(&mut self, row: usize)
+    {
+        self.items.swap_remove(row);
+        self.claims.swap_remove(row);
+        self.added.swap_remove(row);
+        self.changed.swap_remove(row);
+    }
+}
+
+impl Archetype
+{
+    fn new(index: usize, components: Vec<usize>, empty_columns: &[Box<dyn Column>]) -> Self
+    {
+        let size = components.iter().max().map_or(0, |id| id + 1);
+        let mut column_of = vec![None; size];
+
+        for (column, id) in components.iter().enumerate()
+        {
+            column_of[*id] = Some(column);
+        }
+
+        let columns = components
+            .iter()
+            .map(|id| empty_columns[*id].empty())
+            .collect();
+
+        Self {
+            index,
+            components,
+            columns,
+            column_of,
+            adding: Vec::new(),
+            entities: Vec::new(),
+        }
+    }
+
+    /// Position of the archetype in the tables, it never changes.
+    #[inline]
+    pub fn index(&self) -> usize
+    {
+        self.index
+    }
+
+    #[inline]
+    pub fn entities(&self) -> &[Entity]
+    {
+        &self.entities
+    }
+
+    /// Typed column of the component, the queries look it up once per archetype.
+    pub fn column<T: 'static>(&self, id: usize) -> Option<&TableColumn<T>>
+    {
+        self.columns[self.column_index(id)?]
+            .as_any()
+            .downcast_ref::<TableColumn<T>>()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: usize) -> bool
+    {
+        self.column_index(id).is_some()
+    }
+
+    #[inline]
+    fn column_index(&self, id: usize) -> Option<usize>
+    {
+        self.column_of.get(id).copied().flatten()
+    }
+}
+
+impl Tables
+{
+    pub fn register<T: Send + Sync + 'static>(&mut self)
+    {
+        let type_id = TypeId::of::<T>();
+
+        if self.ids.contains_key(&type_id)
+        {
+            return;
+        }
+
+        self.ids.insert(type_id, self.empty_columns.len());
+        self.empty_columns.push(Box::new(TableColumn::<T>::new()));
+        self.containing.push(Vec::new());
+        self.removed.push(Vec::new());
+    }
+
+    #[inline]
+    pub fn id_of<T: 'static>(&self) -> Option<usize>
+    {
+        self.ids.get(&TypeId::of::<T>()).copied()
+    }
+
+    /// Archetypes having the component, they are never removed so the order is stable.
+    pub(crate) fn archetypes_with(&self, id: usize) -> impl Iterator<Item = &Archetype>
+    {
+        self.containing[id]
+            .iter()
+            .map(|archetype| &self.archetypes[*archetype])
+    }
+
+    /// Number of entities having the component.
+    pub fn count(&self, id: usize) -> usize
+    {
+        self.archetypes_with(id)
+            .map(|archetype| archetype.entities.len())
+            .sum()
+    }
+
+    pub fn get<T: 'static>(&self, id: usize, entity: Entity) -> Option<ComponentCells<'_, T>>
+    {
+        let (archetype, row) = self.row_of(id, entity)?;
+
+        self.archetypes[archetype]
+            .column::<T>(id)
+            .map(|column| column.cells(row))
+    }
+
+    /// Archetype and row of the entity, if it has the component.
+    pub fn row_of(&self, id: usize, entity: Entity) -> Option<(usize, usize)>
+    {
+        let location = self.location(entity)?;
+
+        self.archetypes[location.archetype]
+            .column_index(id)
+            .map(|_| (location.archetype, location.row))
+    }
+
+    #[inline]
+    pub fn removed(&self, id: usize) -> &[(Entity, u64)]
+    {
+        &self.removed[id]
+    }
+
+    pub fn clear_removed(&mut self, tick: u64)
+    {
+        self.removed
+            .iter_mut()
+            .for_each(|removed| removed.retain(|(_, at)| *at > tick));
+    }
+
+    /// Adds or replaces the entity component, adding moves the entity into another archetype.
+    pub fn insert<T: 'static>(&mut self, id: usize, entity: Entity, item: T, tick: u64)
+    {
+        if let Some(cells) = self.get::<T>(id, entity)
+        {
+            *cells.item.borrow_mut() = item;
+            cells.changed.set(tick);
+            return;
+        }
+
+        let current = self.location(entity);
+        let target = self.archetype_adding(current.map(|location| location.archetype), id);
+
+        if let Some(location) = current
+        {
+            self.move_entity(location, target, None);
+        }
+        else
+        {
+            self.archetypes[target].entities.push(entity);
+        }
+
+        let archetype = &mut self.archetypes[target];
+        let column = archetype
+            .column_index(id)
+            .expect("The target archetype has the component.");
+
+        archetype.columns[column]
+            .as_any_mut()
+            .downcast_mut::<TableColumn<T>>()
+            .expect("The column has the type of the component.")
+            .push(item, tick, tick);
+
+        let row = archetype.entities.len() - 1;
+        self.set_location(entity, target, row);
+    }
+
+    /// Takes the entity component out, which moves the entity into another archetype.
+    pub fn remove<T: 'static>(&mut self, id: usize, entity: Entity, tick: u64) -> Option<T>
+    {
+        let location = self.location(entity)?;
+        let source = &mut self.archetypes[location.archetype];
+        let column = source.column_index(id)?;
+
+        let item = source.columns[column]
+            .as_any_mut()
+            .downcast_mut::<TableColumn<T>>()
+            .expect("The column has the type of the component.")
+            .take(location.row);
+
+        let mut components = source.components.clone();
+        components.retain(|other| *other != id);
+        self.removed[id].push((entity, tick));
+
+        if components.is_empty()
+        {
+            self.archetypes[location.archetype]
+                .entities
+                .swap_remove(location.row);
+            self.locations[entity.index()] = None;
+            self.fix_moved(location);
+        }
+        else
+        {
+            let target = self.archetype(components);
+            self.move_entity(location, target, Some(id));
+
+            let row = self.archetypes[target].entities.len() - 1;
+            self.set_location(entity, target, row);
+        }
+        Some(item)
+    }
+
+    /// Drops all of the entity components.
+    pub fn remove_entity(&mut self, entity: Entity, tick: u64)
+    {
+        let Some(location) = self.location(entity)
+        else
+        {
+            return;
+        };
+
+        let archetype = &mut self.archetypes[location.archetype];
+
+        archetype
+            .columns
+            .iter_mut()
+            .for_each(|column| column.swap_remove(location.row));
+        archetype.entities.swap_remove(location.row);
+
+        for id in &archetype.components
+        {
+            self.removed[*id].push((entity, tick));
+        }
+
+        self.locations[entity.index()] = None;
+        self.fix_moved(location);
+    }
+
+    // Archetype of the entities from the `source` archetype which get the component, the result is
+    // remembered by the source since every spawned entity takes the same path.
+    fn archetype_adding(&mut self, source: Option<usize>, id: usize) -> usize
+    {
+        let cached =
+            source.and_then(|source| self.archetypes[source].adding.get(id).copied().flatten());
+
+        if let Some(target) = cached
+        {
+            return target;
+        }
+
+        let mut components = source.map_or_else(Vec::new, |source| {
+            self.archetypes[source].components.clone()
+        });
+        components.push(id);
+        components.sort_unstable();
+
+        let target = self.archetype(components);
+
+        if let Some(source) = source
+        {
+            let adding = &mut self.archetypes[source].adding;
+
+            if adding.len() <= id
+            {
+                adding.resize(id + 1, None);
+            }
+            adding[id] = Some(target);
+        }
+        target
+    }
+
+    fn archetype(&mut self, components: Vec<usize>) -> usize
+    {
+        if let Some(archetype) = self.archetype_of.get(&components)
+        {
+            return *archetype;
+        }
+
+        let index = self.archetypes.len();
+
+        for id in &components
+        {
+            self.containing[*id].push(index);
+        }
+
+        self.archetypes.push(Archetype::new(
+            index,
+            components.clone(),
+            &self.empty_columns,
+        ));
+        self.archetype_of.insert(components, index);
+
+        index
+    }
+
+    // Moves the shared components of the entity, the new one has to be pushed by the caller. The
+    // `taken` component was already taken out of the source archetype by the caller.
+    fn move_entity(&mut self, from: Location, to: usize, taken: Option<usize>)
+    {
+        let (source, target) = if from.archetype < to
+        {
+            let (left, right) = self.archetypes.split_at_mut(to);
+            (&mut left[from.archetype], &mut right[0])
+        }
+        else
+        {
+            let (left, right) = self.archetypes.split_at_mut(from.archetype);
+            (&mut right[0], &mut left[to])
+        };
+
+        for (column, id) in source.components.iter().enumerate()
+        {
+            match target.column_index(*id)
+            {
+                Some(target_column) => source.columns[column]
+                    .move_row(from.row, target.columns[target_column].as_mut()),
+                None if taken == Some(*id) => (),
+                None => source.columns[column].swap_remove(from.row),
+            }
+        }
+
+        let entity = source.entities.swap_remove(from.row);
+        target.entities.push(entity);
+
+        self.fix_moved(from);
+    }
+
+    // Points the entity swapped into the freed row to its new place.
+    fn fix_moved(&mut self, freed: Location)
+    {
+        if let Some(moved) = self.archetypes[freed.archetype].entities.get(freed.row)
+        {
+            let moved = *moved;
+            self.set_location(moved, freed.archetype, freed.row);
+        }
+    }
+
+    fn location(&self, entity: Entity) -> Option<Location>
+    {
+        let location = self.locations.get(entity.index()).copied().flatten()?;
+        let stored = self.archetypes[location.archetype].entities[location.row];
+
+        (stored == entity).then_some(location)
+    }
+
+    fn set_location(&mut self, entity: Entity, archetype: usize, row: usize)
+    {
+        let index = entity.index();
+
+        if index >= self.locations.len()
+        {
+            self.locations.resize(index + 1, None);
+        }
+        self.locations[index] = Some(Location { archetype, row });
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn entity(index: u32) -> Entity
+    {
+        Entity::new(index, 0)
+    }
+
+    fn tables() -> (Tables, usize, usize)
+    {
+        let mut tables = Tables::default();
+        tables.register::<u32>();
+        tables.register::<i64>();
+
+        let first = tables.id_of::<u32>().unwrap();
+        let second = tables.id_of::<i64>().unwrap();
+
+        (tables, first, second)
+    }
+
+    fn value<T: Copy + 'static>(tables: &Tables, id: usize, entity: Entity) -> Option<T>
+    {
+        tables
+            .get::<T>(id, entity)
+            .map(|cells| *cells.item.borrow())
+    }
+
+    #[test]
+    fn entities_with_same_components_share_archetype()
+    {
+        let (mut tables, first, second) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 0);
+        tables.insert(second, entity(0), 2_i64, 0);
+        tables.insert(first, entity(1), 3_u32, 0);
+        tables.insert(second, entity(1), 4_i64, 0);
+
+        assert_eq!(tables.archetypes.len(), 2);
+        assert_eq!(tables.archetypes[1].entities(), &[entity(0), entity(1)]);
+        assert_eq!(tables.count(first), 2);
+        assert_eq!(value::<i64>(&tables, second, entity(1)), Some(4));
+    }
+
+    #[test]
+    fn moving_entity_keeps_other_rows_valid()
+    {
+        let (mut tables, first, second) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 0);
+        tables.insert(first, entity(1), 2_u32, 0);
+        tables.insert(first, entity(2), 3_u32, 0);
+        tables.insert(second, entity(0), 4_i64, 0);
+
+        assert_eq!(value::<u32>(&tables, first, entity(0)), Some(1));
+        assert_eq!(value::<u32>(&tables, first, entity(1)), Some(2));
+        assert_eq!(value::<u32>(&tables, first, entity(2)), Some(3));
+        assert_eq!(value::<i64>(&tables, second, entity(2)), None);
+    }
+
+    #[test]
+    fn replace_marks_component_as_changed()
+    {
+        let (mut tables, first, _) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 2);
+        tables.insert(first, entity(0), 5_u32, 7);
+
+        let cells = tables.get::<u32>(first, entity(0)).unwrap();
+
+        assert_eq!(*cells.item.borrow(), 5);
+        assert_eq!(cells.added.get(), 2);
+        assert_eq!(cells.changed.get(), 7);
+    }
+
+    #[test]
+    fn remove_entity_records_removals()
+    {
+        let (mut tables, first, second) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 0);
+        tables.insert(second, entity(0), 2_i64, 0);
+        tables.insert(first, entity(1), 3_u32, 0);
+        tables.insert(second, entity(1), 4_i64, 0);
+
+        tables.remove_entity(entity(0), 5);
+
+        assert_eq!(value::<u32>(&tables, first, entity(0)), None);
+        assert_eq!(value::<u32>(&tables, first, entity(1)), Some(3));
+        assert_eq!(tables.removed(second), &[(entity(0), 5)]);
+
+        tables.clear_removed(5);
+
+        assert!(tables.removed(first).is_empty());
+    }
+
+    #[test]
+    fn remove_moves_entity_to_smaller_archetype()
+    {
+        let (mut tables, first, second) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 0);
+        tables.insert(second, entity(0), 2_i64, 0);
+        tables.insert(first, entity(1), 3_u32, 0);
+        tables.insert(second, entity(1), 4_i64, 0);
+
+        assert_eq!(tables.remove::<u32>(first, entity(0), 3), Some(1));
+        assert_eq!(tables.remove::<u32>(first, entity(0), 3), None);
+
+        assert_eq!(value::<i64>(&tables, second, entity(0)), Some(2));
+        assert_eq!(value::<u32>(&tables, first, entity(1)), Some(3));
+        assert_eq!(value::<i64>(&tables, second, entity(1)), Some(4));
+        assert_eq!(tables.removed(first), &[(entity(0), 3)]);
+
+        assert_eq!(tables.remove::<i64>(second, entity(0), 4), Some(2));
+        assert_eq!(value::<i64>(&tables, second, entity(0)), None);
+        assert_eq!(tables.count(second), 1);
+    }
+
+    #[test]
+    fn stale_entity_is_not_found()
+    {
+        let (mut tables, first, _) = tables();
+
+        tables.insert(first, entity(0), 1_u32, 0);
+
+        assert!(tables.get::<u32>(first, Entity::new(0, 1)).is_none());
+    }
+}
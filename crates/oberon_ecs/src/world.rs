@@ -1,105 +1,876 @@
-use std::any::TypeId;
-use std::cell::{Ref, RefMut};
+use std::any::{type_name, Any, TypeId};
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::fmt::Debug;
 
-use crate::entity::EntityBuilder;
+use crate::allocator::EntityAllocator;
+use crate::cell::{AtomicRefCell, Ref, RefMut, TickCell};
+use crate::change::{Mut, Ticks};
+use crate::entity::{Entity, EntityBuilder};
+use crate::error::EcsError;
+use crate::event::{self, Events};
+use crate::hierarchy::{Children, Parent};
+use crate::inspect::{ComponentCount, ComponentInfo, ComponentView};
+use crate::query::{Query, QueryData, QueryFilter};
+#[cfg(feature = "serde")]
+use crate::snapshot::{
+    ComponentSerde, EntityMap, EntitySnapshot, MapEntities, Snapshot, SnapshotError, SnapshotFormat,
+};
 use crate::sparse_set::{ComponentStorage, SparseSet};
+use crate::storage::{ComponentCells, Storage, StorageType};
+use crate::table::Tables;
 
 pub struct World
 {
-    size: usize,
-    current_id: usize,
+    entities: EntityAllocator,
     components: HashMap<TypeId, Box<dyn ComponentStorage>>,
+    tables: Tables,
+    infos: HashMap<TypeId, ComponentInfo>,
+    resources: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    event_updaters: Vec<fn(&World)>,
+    change_tick: TickCell,
+    last_change_tick: u64,
+    #[cfg(feature = "serde")]
+    serializers: Vec<ComponentSerde>,
+}
+
+impl Default for World
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
 }
 
 impl World
 {
-    pub fn new(size: usize) -> Self
+    pub fn new() -> Self
     {
-        Self {
-            size,
-            current_id: 0,
+        let world = Self {
+            entities: EntityAllocator::default(),
             components: HashMap::new(),
+            tables: Tables::default(),
+            infos: HashMap::new(),
+            resources: HashMap::new(),
+            event_updaters: Vec::new(),
+            change_tick: TickCell::new(1),
+            last_change_tick: 0,
+            #[cfg(feature = "serde")]
+            serializers: Vec::new(),
+        };
+
+        #[cfg(feature = "serde")]
+        let world = world
+            .serializable_with_entities::<Parent>("Parent")
+            .serializable_with_entities::<Children>("Children");
+
+        world.debuggable::<Parent>().debuggable::<Children>()
+    }
+
+    /// Removes all of the entity components together with all of its descendants, returns
+    /// `false` if the entity was not alive.
+    pub fn despawn(&mut self, entity: Entity) -> bool
+    {
+        if !self.is_alive(entity)
+        {
+            return false;
+        }
+        self.remove_parent(entity);
+
+        let tick = self.change_tick();
+
+        for entity in self.descendants(entity)
+        {
+            self.entities.deallocate(entity);
+            self.components
+                .values_mut()
+                .for_each(|components| components.delete(entity, tick));
+            self.tables.remove_entity(entity, tick);
+        }
+        true
+    }
+
+    /// Panics if the component is borrowed mutably, see `try_get`.
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<Ref<'_, T>>
+    {
+        self.storage::<T>()
+            .and_then(|storage| storage.get(entity))
+            .map(|cells| cells.item.borrow())
+    }
+
+    /// Writing through the returned value marks the component as changed. Panics if the
+    /// component is already borrowed, see `try_get_mut`.
+    pub fn get_mut<T: 'static>(&self, entity: Entity) -> Option<Mut<'_, T>>
+    {
+        let tick = self.change_tick();
+
+        self.storage::<T>()
+            .and_then(|storage| storage.get(entity))
+            .map(|cells| Mut::new(cells.item.borrow_mut(), cells.changed, tick))
+    }
+
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool
+    {
+        self.entities.is_alive(entity)
+    }
+
+    /// Number of alive entities.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// Panics if the query would borrow any component mutably more than once. The change
+    /// filters match the changes made since the last `clear_trackers` call.
+    #[inline]
+    pub fn query<D: QueryData>(&self) -> Query<'_, D>
+    {
+        Query::new(self, self.ticks())
+    }
+
+    #[inline]
+    pub fn query_filtered<D: QueryData, F: QueryFilter>(&self) -> Query<'_, D, F>
+    {
+        Query::new(self, self.ticks())
+    }
+
+    /// Registers the component type in a sparse set.
+    #[inline]
+    pub fn register<T: Send + Sync + 'static>(self) -> Self
+    {
+        self.register_with::<T>(StorageType::SparseSet)
+    }
+
+    /// Registering an already registered type does not change its storage. The types inserted
+    /// without being registered end up in a sparse set.
+    pub fn register_with<T: Send + Sync + 'static>(mut self, storage_type: StorageType) -> Self
+    {
+        self.register_storage::<T>(storage_type);
+        self
+    }
+
+    pub fn spawn(&mut self) -> EntityBuilder<'_>
+    {
+        let entity = self.entities.allocate();
+        EntityBuilder::new(entity, self)
+    }
+
+    /// Builder adding and removing the components of an existing entity.
+    pub fn entity_mut(&mut self, entity: Entity) -> Option<EntityBuilder<'_>>
+    {
+        self.is_alive(entity)
+            .then(|| EntityBuilder::new(entity, self))
+    }
+
+    /// Adds or replaces the entity component, returns `false` if the entity is not alive. A
+    /// `Parent` or `Children` links the entities like `World::set_parent`, so it is refused too
+    /// when the link would make a cycle.
+    #[inline]
+    pub fn insert<T: Send + Sync + 'static>(&mut self, entity: Entity, component: T) -> bool
+    {
+        self.try_insert(entity, component).is_ok()
+    }
+
+    /// Takes the component out of the entity, the removal is seen by `RemovedComponents`.
+    /// Removing a `Parent` or `Children` detaches the entities like `World::remove_parent`.
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T>
+    {
+        if !self.is_alive(entity)
+        {
+            return None;
+        }
+
+        if TypeId::of::<T>() == TypeId::of::<Parent>()
+        {
+            let parent = self.remove_parent(entity)?;
+            return downcast(Parent(parent)).ok();
+        }
+
+        if TypeId::of::<T>() == TypeId::of::<Children>()
+        {
+            let children = self.get::<Children>(entity)?.to_vec();
+
+            for &child in &children
+            {
+                self.remove_parent(child);
+            }
+            return downcast(Children(children)).ok();
+        }
+        self.remove_component(entity)
+    }
+}
+
+/// Fallible variants of the methods which panic or return `None` without telling why.
+impl World
+{
+    pub fn try_get<T: 'static>(&self, entity: Entity) -> Result<Ref<'_, T>, EcsError>
+    {
+        self.component_cells::<T>(entity)?
+            .item
+            .try_borrow()
+            .ok_or(EcsError::BorrowConflict(type_name::<T>()))
+    }
+
+    pub fn try_get_mut<T: 'static>(&self, entity: Entity) -> Result<Mut<'_, T>, EcsError>
+    {
+        let cells = self.component_cells::<T>(entity)?;
+        let item = cells
+            .item
+            .try_borrow_mut()
+            .ok_or(EcsError::BorrowConflict(type_name::<T>()))?;
+
+        Ok(Mut::new(item, cells.changed, self.change_tick()))
+    }
+
+    /// Fails instead of panicking if the query would borrow a component mutably more than once.
+    #[inline]
+    pub fn try_query<D: QueryData>(&self) -> Result<Query<'_, D>, EcsError>
+    {
+        Query::try_new(self, self.ticks())
+    }
+
+    #[inline]
+    pub fn try_query_filtered<D: QueryData, F: QueryFilter>(
+        &self,
+    ) -> Result<Query<'_, D, F>, EcsError>
+    {
+        Query::try_new(self, self.ticks())
+    }
+
+    pub fn try_insert<T: Send + Sync + 'static>(
+        &mut self, entity: Entity, component: T,
+    ) -> Result<(), EcsError>
+    {
+        if !self.is_alive(entity)
+        {
+            return Err(EcsError::DeadEntity(entity));
+        }
+
+        let component = match downcast::<T, Parent>(component)
+        {
+            Ok(parent) => return self.try_set_parent(entity, parent.get()),
+            Err(component) => component,
+        };
+
+        match downcast::<T, Children>(component)
+        {
+            Ok(children) => self.set_children(entity, children),
+            Err(component) =>
+            {
+                self.insert_component(entity, component);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn try_remove<T: 'static>(&mut self, entity: Entity) -> Result<T, EcsError>
+    {
+        self.component_cells::<T>(entity)?;
+
+        self.remove::<T>(entity).ok_or(EcsError::MissingComponent {
+            entity,
+            component: type_name::<T>(),
+        })
+    }
+
+    /// Stops at the first component which is already borrowed elsewhere.
+    pub fn try_for_each<T: 'static>(
+        &self, mut f: impl FnMut(Entity, Ref<'_, T>),
+    ) -> Result<(), EcsError>
+    {
+        self.registered::<T>()?;
+
+        self.query::<(Entity, &T)>()
+            .try_iter()
+            .try_for_each(|item| item.map(|(entity, item)| f(entity, item)))
+    }
+
+    /// Stops at the first component which is already borrowed elsewhere, for example by the
+    /// closure itself.
+    pub fn try_for_each_mut<T: 'static>(
+        &self, mut f: impl FnMut(Entity, Mut<'_, T>),
+    ) -> Result<(), EcsError>
+    {
+        self.registered::<T>()?;
+
+        self.query::<(Entity, &mut T)>()
+            .try_iter()
+            .try_for_each(|item| item.map(|(entity, item)| f(entity, item)))
+    }
+
+    pub fn try_set_parent(&mut self, child: Entity, parent: Entity) -> Result<(), EcsError>
+    {
+        if let Some(dead) = [child, parent]
+            .into_iter()
+            .find(|&entity| !self.is_alive(entity))
+        {
+            return Err(EcsError::DeadEntity(dead));
+        }
+
+        if self.is_ancestor(child, parent)
+        {
+            return Err(EcsError::HierarchyCycle { child, parent });
+        }
+        self.attach(child, parent);
+        Ok(())
+    }
+
+    pub fn try_resource<T: 'static>(&self) -> Result<Ref<'_, T>, EcsError>
+    {
+        self.get_resource_cell::<T>()
+            .ok_or(EcsError::MissingResource(type_name::<T>()))?
+            .try_borrow()
+            .ok_or(EcsError::BorrowConflict(type_name::<T>()))
+    }
+
+    pub fn try_resource_mut<T: 'static>(&self) -> Result<RefMut<'_, T>, EcsError>
+    {
+        self.get_resource_cell::<T>()
+            .ok_or(EcsError::MissingResource(type_name::<T>()))?
+            .try_borrow_mut()
+            .ok_or(EcsError::BorrowConflict(type_name::<T>()))
+    }
+
+    fn registered<T: 'static>(&self) -> Result<Storage<'_, T>, EcsError>
+    {
+        self.storage::<T>()
+            .ok_or(EcsError::UnregisteredComponent(type_name::<T>()))
+    }
+
+    fn component_cells<T: 'static>(&self, entity: Entity)
+        -> Result<ComponentCells<'_, T>, EcsError>
+    {
+        let storage = self.registered::<T>()?;
+
+        if !self.is_alive(entity)
+        {
+            return Err(EcsError::DeadEntity(entity));
+        }
+        storage.get(entity).ok_or(EcsError::MissingComponent {
+            entity,
+            component: type_name::<T>(),
+        })
+    }
+}
+
+impl World
+{
+    pub fn for_each<T: 'static>(&self, mut f: impl FnMut(Entity, Ref<'_, T>))
+    {
+        self.query::<(Entity, &T)>()
+            .iter()
+            .for_each(|(entity, item)| f(entity, item));
+    }
+
+    pub fn for_each_mut<T: 'static>(&self, mut f: impl FnMut(Entity, Mut<'_, T>))
+    {
+        self.query::<(Entity, &mut T)>()
+            .iter()
+            .for_each(|(entity, item)| f(entity, item));
+    }
+}
+
+impl World
+{
+    /// Attaches the `child` to the `parent`, detaching it from its previous parent first. Returns
+    /// `false` if either of the entities is not alive or the `child` is the `parent` or one of
+    /// its ancestors, see `World::try_set_parent`.
+    #[inline]
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> bool
+    {
+        self.try_set_parent(child, parent).is_ok()
+    }
+
+    fn attach(&mut self, child: Entity, parent: Entity)
+    {
+        self.remove_parent(child);
+
+        let attached = self
+            .get_mut::<Children>(parent)
+            .map(|mut children| children.0.push(child))
+            .is_some();
+
+        if !attached
+        {
+            self.insert_component(parent, Children(vec![child]));
         }
+
+        self.insert_component(child, Parent(parent));
     }
 
-    pub fn despawn(&mut self, id: usize)
+    // Replaces the children of the `parent`, nothing changes if any of them cannot be attached.
+    fn set_children(&mut self, parent: Entity, children: Children) -> Result<(), EcsError>
     {
+        if let Some(&dead) = children.iter().find(|&&child| !self.is_alive(child))
+        {
+            return Err(EcsError::DeadEntity(dead));
+        }
+
+        if let Some(&child) = children
+            .iter()
+            .find(|&&child| self.is_ancestor(child, parent))
+        {
+            return Err(EcsError::HierarchyCycle { child, parent });
+        }
+
+        let previous = self
+            .get::<Children>(parent)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+
+        for child in previous
+        {
+            self.remove_parent(child);
+        }
+
+        for child in children.0
+        {
+            self.attach(child, parent);
+        }
+        Ok(())
+    }
+
+    /// Detaches the entity from its parent, returns the parent.
+    pub fn remove_parent(&mut self, child: Entity) -> Option<Entity>
+    {
+        let parent = self.get::<Parent>(child)?.get();
+
+        self.remove_component::<Parent>(child);
+
+        let empty = self
+            .get_mut::<Children>(parent)
+            .is_some_and(|mut children| {
+                children.0.retain(|&other| other != child);
+                children.is_empty()
+            });
+
+        if empty
+        {
+            self.remove_component::<Children>(parent);
+        }
+        Some(parent)
+    }
+
+    /// Entity together with all of its descendants, parents come before their children.
+    pub fn descendants(&self, entity: Entity) -> Vec<Entity>
+    {
+        let mut entities = vec![entity];
+        let mut index = 0;
+
+        while let Some(&current) = entities.get(index)
+        {
+            if let Some(children) = self.get::<Children>(current)
+            {
+                entities.extend(children.iter());
+            }
+            index += 1;
+        }
+        entities
+    }
+
+    // Returns `true` if the `ancestor` is the `entity` itself or any of its parents.
+    fn is_ancestor(&self, ancestor: Entity, entity: Entity) -> bool
+    {
+        let mut current = Some(entity);
+
+        while let Some(entity) = current
+        {
+            if entity == ancestor
+            {
+                return true;
+            }
+            current = self.get::<Parent>(entity).map(|parent| parent.get());
+        }
+        false
+    }
+}
+
+impl World
+{
+    #[inline]
+    pub fn change_tick(&self) -> u64
+    {
+        self.change_tick.get()
+    }
+
+    /// Starts a new frame for the change detection, called by the schedule every frame. The
+    /// removals older than the previous frame are forgotten.
+    pub fn clear_trackers(&mut self)
+    {
+        let previous = self.last_change_tick;
+
         self.components
             .values_mut()
-            .for_each(|components| components.delete(id))
+            .for_each(|components| components.clear_removed(previous));
+        self.tables.clear_removed(previous);
+
+        self.last_change_tick = self.change_tick();
+        self.increment_change_tick();
+    }
+
+    /// Entities which lost the `T` component since the last `clear_trackers` call.
+    pub fn removed<T: 'static>(&self) -> impl Iterator<Item = Entity> + '_
+    {
+        self.storage::<T>()
+            .into_iter()
+            .flat_map(|storage| storage.removed_since(self.last_change_tick))
     }
 
-    pub fn get<T: 'static>(&self, id: usize) -> Option<Ref<'_, T>>
+    /// Advances the clock, returns the new tick.
+    pub fn increment_change_tick(&self) -> u64
     {
-        self.get_storage::<T>()
-            .and_then(|storage| storage.get(id))
-            .map(|value| value.item.borrow())
+        self.change_tick.increment()
     }
 
-    pub fn get_mut<T: 'static>(&self, id: usize) -> Option<RefMut<'_, T>>
+    fn ticks(&self) -> Ticks
     {
-        self.get_storage::<T>()
-            .and_then(|storage| storage.get(id))
-            .map(|value| value.item.borrow_mut())
+        Ticks {
+            last_run: self.last_change_tick,
+            this_run: self.change_tick(),
+        }
+    }
+}
+
+impl World
+{
+    /// Inserts the `Events<T>` resource, updated by `update_events`.
+    pub fn add_event<T: Send + Sync + 'static>(mut self) -> Self
+    {
+        if !self.contains_resource::<Events<T>>()
+        {
+            self.insert_resource(Events::<T>::default());
+            self.event_updaters.push(event::update_events::<T>);
+        }
+        self
+    }
+
+    /// Panics if the event was not added to the world.
+    pub fn send_event<T: 'static>(&self, event: T)
+    {
+        self.resource_mut::<Events<T>>()
+            .expect("The event has to be added to the world first.")
+            .send(event);
     }
 
-    pub fn register<T: 'static>(mut self) -> Self
+    /// Drops the events sent before the previous update, called by the schedule every frame.
+    pub fn update_events(&mut self)
+    {
+        self.event_updaters.iter().for_each(|update| update(self));
+    }
+
+    #[inline]
+    pub fn contains_resource<T: 'static>(&self) -> bool
+    {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Stores a global value, returns the previous one of the same type.
+    pub fn insert_resource<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T>
     {
         let type_id = TypeId::of::<T>();
 
-        self.components
-            .entry(type_id)
-            .or_insert_with(|| Box::new(SparseSet::<T>::new(self.size)));
+        self.resources
+            .insert(type_id, Box::new(AtomicRefCell::new(value)))
+            .and_then(|previous| previous.downcast::<AtomicRefCell<T>>().ok())
+            .map(|previous| previous.into_inner())
+    }
+
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T>
+    {
+        let type_id = TypeId::of::<T>();
+
+        self.resources
+            .remove(&type_id)
+            .and_then(|value| value.downcast::<AtomicRefCell<T>>().ok())
+            .map(|value| value.into_inner())
+    }
+
+    pub fn resource<T: 'static>(&self) -> Option<Ref<'_, T>>
+    {
+        self.get_resource_cell::<T>().map(AtomicRefCell::borrow)
+    }
+
+    pub fn resource_mut<T: 'static>(&self) -> Option<RefMut<'_, T>>
+    {
+        self.get_resource_cell::<T>().map(AtomicRefCell::borrow_mut)
+    }
+
+    fn get_resource_cell<T: 'static>(&self) -> Option<&AtomicRefCell<T>>
+    {
+        let type_id = TypeId::of::<T>();
+
+        self.resources
+            .get(&type_id)
+            .and_then(|value| value.downcast_ref::<AtomicRefCell<T>>())
+    }
+}
+
+impl World
+{
+    /// Lets `inspect` show the value of the component. The storage of the component is still
+    /// chosen by `register_with` or by the first insert.
+    pub fn debuggable<T: Debug + Send + Sync + 'static>(mut self) -> Self
+    {
+        let info = self
+            .infos
+            .entry(TypeId::of::<T>())
+            .or_insert_with(ComponentInfo::new::<T>);
+
+        *info = info.with_debug::<T>();
         self
     }
 
-    pub fn spawn(&mut self) -> EntityBuilder<'_>
+    /// Components of the entity sorted by their type names, `None` if the entity is not alive.
+    pub fn inspect(&self, entity: Entity) -> Option<Vec<ComponentView>>
+    {
+        if !self.is_alive(entity)
+        {
+            return None;
+        }
+
+        let mut views: Vec<_> = self
+            .infos
+            .values()
+            .filter(|info| (info.contains)(self, entity))
+            .map(|info| info.view(self, entity))
+            .collect();
+
+        views.sort_by_key(|view| view.name);
+        Some(views)
+    }
+
+    /// Number of entities having each of the registered components, sorted by the type names.
+    pub fn component_counts(&self) -> Vec<ComponentCount>
     {
-        let spawned_entity_id = self.current_id;
-        self.current_id += 1;
+        let mut counts: Vec<_> = self
+            .infos
+            .values()
+            .map(|info| ComponentCount {
+                name: info.name,
+                count: (info.count)(self),
+            })
+            .collect();
 
-        EntityBuilder::new(spawned_entity_id, self)
+        counts.sort_by_key(|count| count.name);
+        counts
     }
 }
 
+#[cfg(feature = "serde")]
 impl World
 {
-    pub fn for_each<T: 'static>(&self, mut f: impl FnMut(usize, Ref<'_, T>))
+    /// Lets the component be saved under the `name`. The storage of the component is still chosen
+    /// by `register_with` or by the first insert.
+    pub fn serializable<T>(self, name: &'static str) -> Self
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+    {
+        self.add_serializer(ComponentSerde::new::<T>(name))
+    }
+
+    /// Like `serializable`, the entity handles held by the component are remapped on load. The
+    /// component is not loaded if `MapEntities::map_entities` refuses it.
+    pub fn serializable_with_entities<T>(self, name: &'static str) -> Self
+    where
+        T: MapEntities + serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+    {
+        self.add_serializer(ComponentSerde::with_entities::<T>(name))
+    }
+
+    /// Saves all of the entities, the components are written in the `format`.
+    pub fn save(&self, format: SnapshotFormat) -> Result<Snapshot, SnapshotError>
+    {
+        let entities: Vec<_> = self.query::<Entity>().iter().collect();
+        self.save_entities(entities, format)
+    }
+
+    /// Saves the serializable components of the entities, the dead ones are skipped.
+    pub fn save_entities(
+        &self, entities: impl IntoIterator<Item = Entity>, format: SnapshotFormat,
+    ) -> Result<Snapshot, SnapshotError>
     {
-        if let Some(storage) = self.get_storage::<T>()
+        let mut snapshot = Snapshot::new(format);
+
+        for entity in entities.into_iter().filter(|&entity| self.is_alive(entity))
         {
-            storage
-                .get_all()
-                .iter()
-                .for_each(|entity| f(entity.id, entity.item.borrow()))
+            let mut components = BTreeMap::new();
+
+            for serializer in &self.serializers
+            {
+                if let Some(value) = (serializer.save)(self, entity, format)
+                {
+                    let value = value.map_err(|error| SnapshotError::Component {
+                        name: serializer.name,
+                        error: Box::new(error),
+                    })?;
+                    components.insert(serializer.name.to_string(), value);
+                }
+            }
+            snapshot
+                .entities
+                .push(EntitySnapshot { entity, components });
         }
+        Ok(snapshot)
     }
 
-    pub fn for_each_mut<T: 'static>(&self, mut f: impl FnMut(usize, RefMut<'_, T>))
+    /// Spawns the saved entities next to the existing ones. Every component is deserialized
+    /// first, so nothing is loaded if the snapshot contains an unknown or invalid component.
+    pub fn load(&mut self, snapshot: &Snapshot) -> Result<EntityMap, SnapshotError>
     {
-        if let Some(storage) = self.get_storage::<T>()
+        let mut components = Vec::new();
+
+        for (index, saved) in snapshot.entities.iter().enumerate()
+        {
+            for (name, value) in &saved.components
+            {
+                let serializer = self
+                    .serializer(name)
+                    .ok_or_else(|| SnapshotError::UnknownComponent(name.clone()))?;
+                let component =
+                    (serializer.load)(value).map_err(|error| SnapshotError::Component {
+                        name: serializer.name,
+                        error: Box::new(error),
+                    })?;
+
+                components.push((index, component));
+            }
+        }
+
+        let mut map = EntityMap::default();
+        let loaded: Vec<_> = snapshot
+            .entities
+            .iter()
+            .map(|saved| {
+                let entity = self.spawn().into_id();
+                map.insert(saved.entity, entity);
+                entity
+            })
+            .collect();
+
+        for (index, component) in components
         {
-            storage
-                .get_all()
-                .iter()
-                .for_each(|entity| f(entity.id, entity.item.borrow_mut()))
+            component(self, loaded[index], &map);
         }
+        Ok(map)
+    }
+
+    fn serializer(&self, name: &str) -> Option<ComponentSerde>
+    {
+        self.serializers
+            .iter()
+            .find(|serializer| serializer.name == name)
+            .copied()
+    }
+
+    fn add_serializer(mut self, serializer: ComponentSerde) -> Self
+    {
+        self.serializers
+            .retain(|other| other.name != serializer.name);
+        self.serializers.push(serializer);
+        self
     }
 }
 
 impl World
 {
-    pub(crate) fn get_storage<T: 'static>(&self) -> Option<&SparseSet<T>>
+    #[inline]
+    pub(crate) fn entities(&self) -> &EntityAllocator
+    {
+        &self.entities
+    }
+
+    /// Makes the entities reserved by the commands alive, without any components.
+    #[inline]
+    pub(crate) fn flush_entities(&mut self)
+    {
+        self.entities.flush();
+    }
+
+    /// Adds or replaces the entity component, registering its type if needed.
+    pub(crate) fn insert_component<T: Send + Sync + 'static>(
+        &mut self, entity: Entity, component: T,
+    )
+    {
+        let tick = self.change_tick();
+        self.register_storage::<T>(StorageType::SparseSet);
+
+        if let Some(storage) = self.get_sparse_set_mut::<T>()
+        {
+            storage.add(entity, component, tick);
+        }
+        else if let Some(id) = self.tables.id_of::<T>()
+        {
+            self.tables.insert(id, entity, component, tick);
+        }
+    }
+
+    // Takes the component out without keeping the hierarchy in sync.
+    fn remove_component<T: 'static>(&mut self, entity: Entity) -> Option<T>
+    {
+        let tick = self.change_tick();
+
+        if let Some(storage) = self.get_sparse_set_mut::<T>()
+        {
+            return storage.remove(entity, tick);
+        }
+
+        let id = self.tables.id_of::<T>()?;
+        self.tables.remove(id, entity, tick)
+    }
+
+    fn register_storage<T: Send + Sync + 'static>(&mut self, storage_type: StorageType)
     {
+        if self.storage::<T>().is_some()
+        {
+            return;
+        }
+
         let type_id = TypeId::of::<T>();
 
-        self.components
-            .get(&type_id)
-            .and_then(|components| components.as_any().downcast_ref::<SparseSet<T>>())
+        match storage_type
+        {
+            StorageType::SparseSet =>
+            {
+                self.components
+                    .insert(type_id, Box::new(SparseSet::<T>::new()));
+            }
+            StorageType::Table => self.tables.register::<T>(),
+        }
+        self.infos
+            .entry(type_id)
+            .or_insert_with(ComponentInfo::new::<T>);
     }
 
-    pub(crate) fn get_storage_mut<T: 'static>(&mut self) -> Option<&mut SparseSet<T>>
+    pub(crate) fn storage<T: 'static>(&self) -> Option<Storage<'_, T>>
+    {
+        let type_id = TypeId::of::<T>();
+
+        let sparse = self.components.get(&type_id).and_then(|components| {
+            let erased = components.as_ref();
+
+            erased
+                .as_any()
+                .downcast_ref::<SparseSet<T>>()
+                .map(|storage| Storage::Sparse(storage, erased))
+        });
+
+        sparse.or_else(|| {
+            self.tables
+                .id_of::<T>()
+                .map(|id| Storage::Table(&self.tables, id))
+        })
+    }
+
+    fn get_sparse_set_mut<T: 'static>(&mut self) -> Option<&mut SparseSet<T>>
     {
         let type_id = TypeId::of::<T>();
 
@@ -109,23 +880,104 @@ impl World
     }
 }
 
+// Gives the value back unchanged if `T` is not `U`.
+fn downcast<T: 'static, U: 'static>(value: T) -> Result<U, T>
+{
+    if TypeId::of::<T>() != TypeId::of::<U>()
+    {
+        return Err(value);
+    }
+    let value: Box<dyn Any> = Box::new(value);
+
+    Ok(*value.downcast::<U>().expect("the types are the same"))
+}
+
 #[cfg(test)]
 mod tests
 {
     use super::*;
 
     #[test]
-    #[should_panic]
     fn spawn_entity_with_unregistered_component()
     {
-        let mut world = World::new(10);
-        world.spawn().with::<u32>(25).into_id();
+        let mut world = World::new();
+
+        assert_eq!(
+            world.try_get::<u32>(Entity::new(0, 0)).err(),
+            Some(EcsError::UnregisteredComponent("u32"))
+        );
+
+        let entity = world.spawn().with::<u32>(25).into_id();
+
+        assert_eq!(*world.get::<u32>(entity).unwrap(), 25);
+        assert_eq!(world.query::<&u32>().iter().count(), 1);
+    }
+
+    #[test]
+    fn try_variants_report_why_they_fail()
+    {
+        let mut world = World::new().register::<u32>();
+        let entity = world.spawn().with::<u32>(1).into_id();
+        let empty = world.spawn().into_id();
+        world.despawn(empty);
+
+        assert_eq!(
+            world.try_get::<u32>(empty).err(),
+            Some(EcsError::DeadEntity(empty))
+        );
+        assert_eq!(
+            world.try_insert::<u32>(empty, 2),
+            Err(EcsError::DeadEntity(empty))
+        );
+        assert_eq!(
+            world.try_resource::<String>().err(),
+            Some(EcsError::MissingResource("alloc::string::String"))
+        );
+        assert_eq!(world.try_remove::<u32>(entity), Ok(1));
+        assert_eq!(
+            world.try_remove::<u32>(entity),
+            Err(EcsError::MissingComponent {
+                entity,
+                component: "u32"
+            })
+        );
+    }
+
+    #[test]
+    fn borrow_conflicts_are_recoverable()
+    {
+        let mut world = World::new().register::<u32>();
+        let first = world.spawn().with::<u32>(1).into_id();
+        world.spawn().with::<u32>(2);
+
+        let held = world.get_mut::<u32>(first).unwrap();
+
+        assert_eq!(
+            world.try_get::<u32>(first).err(),
+            Some(EcsError::BorrowConflict("u32"))
+        );
+        assert!(world.try_get_mut::<u32>(first).is_err());
+
+        let mut visited = 0;
+        let result = world.try_for_each_mut::<u32>(|_, _| visited += 1);
+
+        assert_eq!(result, Err(EcsError::BorrowConflict("u32")));
+        assert_eq!(visited, 0);
+
+        drop(held);
+
+        assert_eq!(
+            world.try_for_each_mut::<u32>(|_, mut value| *value += 1),
+            Ok(())
+        );
+        assert_eq!(*world.try_get::<u32>(first).unwrap(), 2);
+        assert!(world.try_query::<(&mut u32, &mut u32)>().is_err());
     }
 
     #[test]
     fn spawn_entity()
     {
-        let mut world = World::new(10).register::<u32>().register::<String>();
+        let mut world = World::new().register::<u32>().register::<String>();
 
         let entity_id = world
             .spawn()
@@ -143,7 +995,7 @@ mod tests
     #[test]
     fn despawn_entity()
     {
-        let mut world = World::new(10).register::<u32>().register::<i32>();
+        let mut world = World::new().register::<u32>().register::<i32>();
         let entity_id = world.spawn().with::<u32>(25).with::<i32>(-10).into_id();
 
         world.despawn(entity_id);
@@ -155,10 +1007,52 @@ mod tests
         assert!(v2.is_none());
     }
 
+    #[test]
+    fn despawned_entity_is_not_alive()
+    {
+        let mut world = World::new().register::<u32>();
+        let entity = world.spawn().with::<u32>(25).into_id();
+
+        assert!(world.is_alive(entity));
+        assert!(world.despawn(entity));
+        assert!(!world.is_alive(entity));
+        assert!(!world.despawn(entity));
+    }
+
+    #[test]
+    fn stale_handle_does_not_read_recycled_entity()
+    {
+        let mut world = World::new().register::<u32>();
+
+        let stale = world.spawn().with::<u32>(1).into_id();
+        world.despawn(stale);
+
+        let fresh = world.spawn().with::<u32>(2).into_id();
+
+        assert_eq!(stale.index(), fresh.index());
+        assert!(world.get::<u32>(stale).is_none());
+        assert!(world.get_mut::<u32>(stale).is_none());
+        assert_eq!(*world.get::<u32>(fresh).unwrap(), 2);
+    }
+
+    #[test]
+    fn despawn_recycles_indices()
+    {
+        let mut world = World::new().register::<u32>();
+
+        for _ in 0..10
+        {
+            let entity = world.spawn().with::<u32>(1).into_id();
+            world.despawn(entity);
+        }
+
+        assert!(world.is_empty());
+    }
+
     #[test]
     fn for_each_entity_do_action()
     {
-        let mut world = World::new(10).register::<u32>();
+        let mut world = World::new().register::<u32>();
 
         world.spawn().with::<u32>(1);
         world.spawn().with::<u32>(2);
@@ -174,7 +1068,7 @@ mod tests
     #[test]
     fn get_entity_component_doesnt_exist()
     {
-        let mut world = World::new(10).register::<u32>().register::<i32>();
+        let mut world = World::new().register::<u32>().register::<i32>();
         let entity_id = world.spawn().with::<u32>(25).into_id();
 
         let value = world.get::<i32>(entity_id);
@@ -185,8 +1079,8 @@ mod tests
     #[test]
     fn get_entity_component_when_not_registered()
     {
-        let world = World::new(10);
-        let value = world.get::<u32>(0);
+        let world = World::new();
+        let value = world.get::<u32>(Entity::new(0, 0));
 
         assert!(value.is_none());
     }
@@ -194,7 +1088,7 @@ mod tests
     #[test]
     fn get_entity_component()
     {
-        let mut world = World::new(10).register::<u32>();
+        let mut world = World::new().register::<u32>();
         let entity_id = world.spawn().with::<u32>(25).into_id();
 
         let value = world.get::<u32>(entity_id).unwrap();
@@ -205,7 +1099,7 @@ mod tests
     #[test]
     fn get_mut_entity_component_doesnt_exist()
     {
-        let mut world = World::new(10).register::<u32>().register::<i32>();
+        let mut world = World::new().register::<u32>().register::<i32>();
         let entity_id = world.spawn().with::<u32>(25).into_id();
 
         let value = world.get_mut::<i32>(entity_id);
@@ -216,8 +1110,8 @@ mod tests
     #[test]
     fn get_mut_entity_component_when_not_registered()
     {
-        let world = World::new(10);
-        let value = world.get_mut::<u32>(0);
+        let world = World::new();
+        let value = world.get_mut::<u32>(Entity::new(0, 0));
 
         assert!(value.is_none());
     }
@@ -225,7 +1119,7 @@ mod tests
     #[test]
     fn get_mut_entity_component()
     {
-        let mut world = World::new(10).register::<u32>();
+        let mut world = World::new().register::<u32>();
         let entity_id = world.spawn().with::<u32>(25).into_id();
 
         let mut value = world.get_mut::<u32>(entity_id).unwrap();
@@ -240,13 +1134,113 @@ mod tests
         assert_eq!(*value, 36);
     }
 
+    #[test]
+    fn table_components_work_like_sparse_ones()
+    {
+        let mut world = World::new()
+            .register_with::<u32>(StorageType::Table)
+            .register_with::<i64>(StorageType::Table)
+            .register::<String>();
+
+        let first = world.spawn().with::<u32>(1).with::<i64>(-1).into_id();
+        let second = world
+            .spawn()
+            .with::<u32>(2)
+            .with::<String>("second".to_string())
+            .into_id();
+
+        *world.get_mut::<u32>(first).unwrap() += 10;
+
+        let query = world.query::<(Entity, &u32, Option<&i64>, Option<&String>)>();
+        let mut found: Vec<_> = query
+            .iter()
+            .map(|(entity, a, b, c)| (entity, *a, b.map(|b| *b), c.is_some()))
+            .collect();
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![(first, 11, Some(-1), false), (second, 2, None, true)]
+        );
+
+        drop(query);
+        world.despawn(first);
+
+        assert!(world.get::<u32>(first).is_none());
+        assert_eq!(*world.get::<u32>(second).unwrap(), 2);
+        assert_eq!(world.removed::<i64>().collect::<Vec<_>>(), vec![first]);
+    }
+
+    #[test]
+    fn insert_and_remove_components_of_existing_entity()
+    {
+        for storage_type in [StorageType::SparseSet, StorageType::Table]
+        {
+            let mut world = World::new()
+                .register_with::<u32>(storage_type)
+                .register_with::<i64>(storage_type);
+
+            let entity = world.spawn().with::<u32>(1).into_id();
+
+            assert!(world.insert::<i64>(entity, 2));
+            assert_eq!(*world.get::<i64>(entity).unwrap(), 2);
+
+            assert_eq!(world.remove::<u32>(entity), Some(1));
+            assert_eq!(world.remove::<u32>(entity), None);
+            assert!(world.get::<u32>(entity).is_none());
+            assert_eq!(*world.get::<i64>(entity).unwrap(), 2);
+            assert_eq!(world.removed::<u32>().collect::<Vec<_>>(), vec![entity]);
+
+            world
+                .entity_mut(entity)
+                .unwrap()
+                .with::<u32>(3)
+                .remove::<i64>();
+
+            assert_eq!(*world.get::<u32>(entity).unwrap(), 3);
+            assert!(world.get::<i64>(entity).is_none());
+        }
+    }
+
+    #[test]
+    fn existing_entity_has_to_be_alive()
+    {
+        let mut world = World::new().register::<u32>();
+        let entity = world.spawn().with::<u32>(1).into_id();
+        world.despawn(entity);
+
+        assert!(world.entity_mut(entity).is_none());
+        assert!(!world.insert::<u32>(entity, 2));
+        assert_eq!(world.remove::<u32>(entity), None);
+    }
+
+    #[test]
+    fn world_can_be_shared_between_threads()
+    {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<World>();
+    }
+
+    #[test]
+    fn register_keeps_the_first_storage_type()
+    {
+        let world = World::new()
+            .register::<u32>()
+            .register_with::<u32>(StorageType::Table);
+
+        assert!(matches!(world.storage::<u32>(), Some(Storage::Sparse(..))));
+    }
+
     #[test]
     fn register_entity_adds_entry_to_components()
     {
-        let world = World::new(10).register::<u32>();
+        let world = World::new();
+        let builtin = world.components.len();
+        let world = world.register::<u32>();
         let key = TypeId::of::<u32>();
 
-        assert_eq!(world.components.len(), 1);
-        assert_eq!(world.components.contains_key(&key), true);
+        assert_eq!(world.components.len(), builtin + 1);
+        assert!(world.components.contains_key(&key));
     }
 }